@@ -0,0 +1,99 @@
+-- This file should undo anything in `up.sql`
+
+-- #region break counts
+alter table break_count_full_snapshot add column player_uuid char(36) null after full_snapshot_point_id;
+update break_count_full_snapshot inner join player on player.id = break_count_full_snapshot.player_id
+  set break_count_full_snapshot.player_uuid = player.uuid;
+alter table break_count_full_snapshot
+  drop primary key,
+  drop index player_id,
+  drop column player_id,
+  modify player_uuid char(36) not null,
+  add primary key (full_snapshot_point_id, player_uuid),
+  add index player_uuid (player_uuid);
+
+alter table break_count_diff add column player_uuid char(36) null after diff_point_id;
+update break_count_diff inner join player on player.id = break_count_diff.player_id
+  set break_count_diff.player_uuid = player.uuid;
+alter table break_count_diff
+  drop primary key,
+  drop index player_id,
+  drop column player_id,
+  modify player_uuid char(36) not null,
+  add primary key (diff_point_id, player_uuid),
+  add index player_uuid (player_uuid);
+-- #endregion
+
+-- #region build counts
+alter table build_count_full_snapshot add column player_uuid char(36) null after full_snapshot_point_id;
+update build_count_full_snapshot inner join player on player.id = build_count_full_snapshot.player_id
+  set build_count_full_snapshot.player_uuid = player.uuid;
+alter table build_count_full_snapshot
+  drop primary key,
+  drop index player_id,
+  drop column player_id,
+  modify player_uuid char(36) not null,
+  add primary key (full_snapshot_point_id, player_uuid),
+  add index player_uuid (player_uuid);
+
+alter table build_count_diff add column player_uuid char(36) null after diff_point_id;
+update build_count_diff inner join player on player.id = build_count_diff.player_id
+  set build_count_diff.player_uuid = player.uuid;
+alter table build_count_diff
+  drop primary key,
+  drop index player_id,
+  drop column player_id,
+  modify player_uuid char(36) not null,
+  add primary key (diff_point_id, player_uuid),
+  add index player_uuid (player_uuid);
+-- #endregion
+
+-- #region play ticks
+alter table play_ticks_full_snapshot add column player_uuid char(36) null after full_snapshot_point_id;
+update play_ticks_full_snapshot inner join player on player.id = play_ticks_full_snapshot.player_id
+  set play_ticks_full_snapshot.player_uuid = player.uuid;
+alter table play_ticks_full_snapshot
+  drop primary key,
+  drop index player_id,
+  drop column player_id,
+  modify player_uuid char(36) not null,
+  add primary key (full_snapshot_point_id, player_uuid),
+  add index player_uuid (player_uuid);
+
+alter table play_ticks_diff add column player_uuid char(36) null after diff_point_id;
+update play_ticks_diff inner join player on player.id = play_ticks_diff.player_id
+  set play_ticks_diff.player_uuid = player.uuid;
+alter table play_ticks_diff
+  drop primary key,
+  drop index player_id,
+  drop column player_id,
+  modify player_uuid char(36) not null,
+  add primary key (diff_point_id, player_uuid),
+  add index player_uuid (player_uuid);
+-- #endregion
+
+-- #region vote counts
+alter table vote_count_full_snapshot add column player_uuid char(36) null after full_snapshot_point_id;
+update vote_count_full_snapshot inner join player on player.id = vote_count_full_snapshot.player_id
+  set vote_count_full_snapshot.player_uuid = player.uuid;
+alter table vote_count_full_snapshot
+  drop primary key,
+  drop index player_id,
+  drop column player_id,
+  modify player_uuid char(36) not null,
+  add primary key (full_snapshot_point_id, player_uuid),
+  add index player_uuid (player_uuid);
+
+alter table vote_count_diff add column player_uuid char(36) null after diff_point_id;
+update vote_count_diff inner join player on player.id = vote_count_diff.player_id
+  set vote_count_diff.player_uuid = player.uuid;
+alter table vote_count_diff
+  drop primary key,
+  drop index player_id,
+  drop column player_id,
+  modify player_uuid char(36) not null,
+  add primary key (diff_point_id, player_uuid),
+  add index player_uuid (player_uuid);
+-- #endregion
+
+drop table `player`;
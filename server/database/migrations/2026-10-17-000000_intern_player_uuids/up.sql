@@ -0,0 +1,121 @@
+-- 各統計量のテーブルに char(36) の UUID を繰り返し記録する代わりに、
+-- UUID を player テーブルに一度だけ記録し、統計量のテーブルには整数の ID を記録する。
+create table player (
+  id int unsigned primary key not null auto_increment,
+  uuid char(36) not null,
+  unique index uuid (uuid)
+);
+
+insert into player (uuid)
+  select player_uuid from break_count_full_snapshot
+  union
+  select player_uuid from break_count_diff
+  union
+  select player_uuid from build_count_full_snapshot
+  union
+  select player_uuid from build_count_diff
+  union
+  select player_uuid from play_ticks_full_snapshot
+  union
+  select player_uuid from play_ticks_diff
+  union
+  select player_uuid from vote_count_full_snapshot
+  union
+  select player_uuid from vote_count_diff
+  order by player_uuid;
+
+-- #region break counts
+alter table break_count_full_snapshot add column player_id int unsigned null after full_snapshot_point_id;
+update break_count_full_snapshot inner join player on player.uuid = break_count_full_snapshot.player_uuid
+  set break_count_full_snapshot.player_id = player.id;
+alter table break_count_full_snapshot
+  drop primary key,
+  drop index player_uuid,
+  drop column player_uuid,
+  modify player_id int unsigned not null references player(id),
+  add primary key (full_snapshot_point_id, player_id),
+  add index player_id (player_id);
+
+alter table break_count_diff add column player_id int unsigned null after diff_point_id;
+update break_count_diff inner join player on player.uuid = break_count_diff.player_uuid
+  set break_count_diff.player_id = player.id;
+alter table break_count_diff
+  drop primary key,
+  drop index player_uuid,
+  drop column player_uuid,
+  modify player_id int unsigned not null references player(id),
+  add primary key (diff_point_id, player_id),
+  add index player_id (player_id);
+-- #endregion
+
+-- #region build counts
+alter table build_count_full_snapshot add column player_id int unsigned null after full_snapshot_point_id;
+update build_count_full_snapshot inner join player on player.uuid = build_count_full_snapshot.player_uuid
+  set build_count_full_snapshot.player_id = player.id;
+alter table build_count_full_snapshot
+  drop primary key,
+  drop index player_uuid,
+  drop column player_uuid,
+  modify player_id int unsigned not null references player(id),
+  add primary key (full_snapshot_point_id, player_id),
+  add index player_id (player_id);
+
+alter table build_count_diff add column player_id int unsigned null after diff_point_id;
+update build_count_diff inner join player on player.uuid = build_count_diff.player_uuid
+  set build_count_diff.player_id = player.id;
+alter table build_count_diff
+  drop primary key,
+  drop index player_uuid,
+  drop column player_uuid,
+  modify player_id int unsigned not null references player(id),
+  add primary key (diff_point_id, player_id),
+  add index player_id (player_id);
+-- #endregion
+
+-- #region play ticks
+alter table play_ticks_full_snapshot add column player_id int unsigned null after full_snapshot_point_id;
+update play_ticks_full_snapshot inner join player on player.uuid = play_ticks_full_snapshot.player_uuid
+  set play_ticks_full_snapshot.player_id = player.id;
+alter table play_ticks_full_snapshot
+  drop primary key,
+  drop index player_uuid,
+  drop column player_uuid,
+  modify player_id int unsigned not null references player(id),
+  add primary key (full_snapshot_point_id, player_id),
+  add index player_id (player_id);
+
+alter table play_ticks_diff add column player_id int unsigned null after diff_point_id;
+update play_ticks_diff inner join player on player.uuid = play_ticks_diff.player_uuid
+  set play_ticks_diff.player_id = player.id;
+alter table play_ticks_diff
+  drop primary key,
+  drop index player_uuid,
+  drop column player_uuid,
+  modify player_id int unsigned not null references player(id),
+  add primary key (diff_point_id, player_id),
+  add index player_id (player_id);
+-- #endregion
+
+-- #region vote counts
+alter table vote_count_full_snapshot add column player_id int unsigned null after full_snapshot_point_id;
+update vote_count_full_snapshot inner join player on player.uuid = vote_count_full_snapshot.player_uuid
+  set vote_count_full_snapshot.player_id = player.id;
+alter table vote_count_full_snapshot
+  drop primary key,
+  drop index player_uuid,
+  drop column player_uuid,
+  modify player_id int unsigned not null references player(id),
+  add primary key (full_snapshot_point_id, player_id),
+  add index player_id (player_id);
+
+alter table vote_count_diff add column player_id int unsigned null after diff_point_id;
+update vote_count_diff inner join player on player.uuid = vote_count_diff.player_uuid
+  set vote_count_diff.player_id = player.id;
+alter table vote_count_diff
+  drop primary key,
+  drop index player_uuid,
+  drop column player_uuid,
+  modify player_id int unsigned not null references player(id),
+  add primary key (diff_point_id, player_id),
+  add index player_id (player_id);
+-- #endregion
@@ -0,0 +1,14 @@
+-- This file should undo anything in `up.sql`
+
+-- tombstone は not null 制約の下では表現できないため削除する
+delete from break_count_diff where new_value is null;
+alter table break_count_diff modify new_value bigint unsigned not null;
+
+delete from build_count_diff where new_value is null;
+alter table build_count_diff modify new_value bigint unsigned not null;
+
+delete from play_ticks_diff where new_value is null;
+alter table play_ticks_diff modify new_value bigint unsigned not null;
+
+delete from vote_count_diff where new_value is null;
+alter table vote_count_diff modify new_value bigint unsigned not null;
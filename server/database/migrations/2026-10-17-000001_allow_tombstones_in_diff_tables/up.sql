@@ -0,0 +1,6 @@
+-- diff テーブルの new_value が null であるレコードは、
+-- そのプレーヤーが直前のデータ点から消えたことを表す (tombstone)
+alter table break_count_diff modify new_value bigint unsigned null;
+alter table build_count_diff modify new_value bigint unsigned null;
+alter table play_ticks_diff modify new_value bigint unsigned null;
+alter table vote_count_diff modify new_value bigint unsigned null;
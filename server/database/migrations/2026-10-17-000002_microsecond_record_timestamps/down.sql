@@ -0,0 +1,11 @@
+-- This file should undo anything in `up.sql`
+
+-- 秒未満の精度は失われる
+alter table break_count_full_snapshot_point modify record_timestamp datetime not null;
+alter table break_count_diff_point modify record_timestamp datetime not null;
+alter table build_count_full_snapshot_point modify record_timestamp datetime not null;
+alter table build_count_diff_point modify record_timestamp datetime not null;
+alter table play_ticks_full_snapshot_point modify record_timestamp datetime not null;
+alter table play_ticks_diff_point modify record_timestamp datetime not null;
+alter table vote_count_full_snapshot_point modify record_timestamp datetime not null;
+alter table vote_count_diff_point modify record_timestamp datetime not null;
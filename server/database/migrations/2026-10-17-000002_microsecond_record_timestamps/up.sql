@@ -0,0 +1,9 @@
+-- 同じ秒の間に記録されたデータ点を区別できるよう、記録時刻をマイクロ秒精度で保持する
+alter table break_count_full_snapshot_point modify record_timestamp datetime(6) not null;
+alter table break_count_diff_point modify record_timestamp datetime(6) not null;
+alter table build_count_full_snapshot_point modify record_timestamp datetime(6) not null;
+alter table build_count_diff_point modify record_timestamp datetime(6) not null;
+alter table play_ticks_full_snapshot_point modify record_timestamp datetime(6) not null;
+alter table play_ticks_diff_point modify record_timestamp datetime(6) not null;
+alter table vote_count_full_snapshot_point modify record_timestamp datetime(6) not null;
+alter table vote_count_diff_point modify record_timestamp datetime(6) not null;
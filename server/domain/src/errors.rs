@@ -0,0 +1,8 @@
+use thiserror::Error;
+
+/// 引数が不正であるために処理を行えないことを表すエラー。
+///
+/// サーバーはこのエラーをクライアントの誤りとして扱い、 `INVALID_ARGUMENT` や 400 Bad Request として返す。
+#[derive(Debug, Error)]
+#[error("{0}")]
+pub struct InvalidArgumentError(pub String);
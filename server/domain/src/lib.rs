@@ -1,2 +1,6 @@
+pub mod errors;
 pub mod models;
 pub mod repositories;
+
+#[cfg(any(test, feature = "test-fixtures"))]
+pub mod test_fixtures;
@@ -1,7 +1,23 @@
+mod all_stats_snapshot;
 mod player;
+mod ranking;
+mod snapshot_comparison;
+mod snapshot_point;
+mod snapshot_summary;
 mod statistics;
+mod stats_gains;
 mod stats_snapshot;
+mod time_series;
+mod timestamped_stats;
 
+pub use all_stats_snapshot::*;
 pub use player::*;
+pub use ranking::*;
+pub use snapshot_comparison::*;
+pub use snapshot_point::*;
+pub use snapshot_summary::*;
 pub use statistics::*;
+pub use stats_gains::*;
 pub use stats_snapshot::*;
+pub use time_series::*;
+pub use timestamped_stats::*;
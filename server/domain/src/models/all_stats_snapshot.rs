@@ -0,0 +1,92 @@
+use std::collections::HashMap;
+use std::fmt::Debug;
+
+use chrono::{DateTime, Utc};
+
+use super::{BreakCount, BuildCount, PlayTicks, Player, StatsSnapshot, VoteCount};
+
+/// あるプレーヤーの、当システムが蓄積しているすべての種類の統計量。
+/// 各統計量は、その統計量のスナップショットにプレーヤーが含まれていなかった場合 `None` となる。
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PlayerAllStats {
+    pub break_count: Option<BreakCount>,
+    pub build_count: Option<BuildCount>,
+    pub play_ticks: Option<PlayTicks>,
+    pub vote_count: Option<VoteCount>,
+}
+
+/// すべての種類の統計量のスナップショットを、プレーヤーごとにまとめたもの。
+#[derive(Clone)]
+pub struct AllStatsSnapshot {
+    /// 各統計量について、実際に用いられたスナップショットの時刻。
+    /// スナップショットが存在しなかった統計量については `None` となる。
+    pub break_count_utc_timestamp: Option<DateTime<Utc>>,
+    pub build_count_utc_timestamp: Option<DateTime<Utc>>,
+    pub play_ticks_utc_timestamp: Option<DateTime<Utc>>,
+    pub vote_count_utc_timestamp: Option<DateTime<Utc>>,
+    pub player_stats: HashMap<Player, PlayerAllStats>,
+}
+
+impl AllStatsSnapshot {
+    pub fn join(
+        break_count: Option<StatsSnapshot<BreakCount>>,
+        build_count: Option<StatsSnapshot<BuildCount>>,
+        play_ticks: Option<StatsSnapshot<PlayTicks>>,
+        vote_count: Option<StatsSnapshot<VoteCount>>,
+    ) -> Self {
+        let mut player_stats: HashMap<Player, PlayerAllStats> = HashMap::new();
+
+        let break_count_utc_timestamp = break_count.map(|snapshot| {
+            for (player, stats) in snapshot.player_stats {
+                player_stats.entry(player).or_default().break_count = Some(stats);
+            }
+            snapshot.utc_timestamp
+        });
+        let build_count_utc_timestamp = build_count.map(|snapshot| {
+            for (player, stats) in snapshot.player_stats {
+                player_stats.entry(player).or_default().build_count = Some(stats);
+            }
+            snapshot.utc_timestamp
+        });
+        let play_ticks_utc_timestamp = play_ticks.map(|snapshot| {
+            for (player, stats) in snapshot.player_stats {
+                player_stats.entry(player).or_default().play_ticks = Some(stats);
+            }
+            snapshot.utc_timestamp
+        });
+        let vote_count_utc_timestamp = vote_count.map(|snapshot| {
+            for (player, stats) in snapshot.player_stats {
+                player_stats.entry(player).or_default().vote_count = Some(stats);
+            }
+            snapshot.utc_timestamp
+        });
+
+        Self {
+            break_count_utc_timestamp,
+            build_count_utc_timestamp,
+            play_ticks_utc_timestamp,
+            vote_count_utc_timestamp,
+            player_stats,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.player_stats.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.player_stats.is_empty()
+    }
+}
+
+impl Debug for AllStatsSnapshot {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AllStatsSnapshot")
+            .field("break_count_utc_timestamp", &self.break_count_utc_timestamp)
+            .field("build_count_utc_timestamp", &self.build_count_utc_timestamp)
+            .field("play_ticks_utc_timestamp", &self.play_ticks_utc_timestamp)
+            .field("vote_count_utc_timestamp", &self.vote_count_utc_timestamp)
+            .field("player_stats_count", &self.len())
+            .finish()
+    }
+}
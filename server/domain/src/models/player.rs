@@ -3,7 +3,7 @@ use bytes::buf::Buf;
 use std::fmt::Debug;
 use std::str::Utf8Error;
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Copy)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Copy)]
 pub struct PlayerUuidString([u8; 36]);
 
 impl PlayerUuidString {
@@ -26,7 +26,7 @@ impl PlayerUuidString {
     }
 }
 
-#[derive(Debug, Clone, Hash, PartialEq, Eq, Copy)]
+#[derive(Debug, Clone, Hash, PartialEq, Eq, PartialOrd, Ord, Copy)]
 pub struct Player {
     pub uuid: PlayerUuidString,
 }
@@ -0,0 +1,144 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+
+use super::{Player, StatsSnapshot};
+
+/// ランキング上での、あるプレーヤーの順位と値。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RankedPlayer<Value> {
+    /// 1 から始まる順位。
+    /// 同じ値を持つプレーヤーには同じ順位が付き、その次の順位は同順位のプレーヤー数だけ飛ぶ
+    /// (例えば、値が `[30, 20, 20, 10]` であれば順位は `[1, 2, 2, 4]` となる)。
+    pub rank: u64,
+    pub player: Player,
+    pub value: Value,
+}
+
+#[derive(Debug, Clone)]
+pub struct Ranking<Value> {
+    pub utc_timestamp: DateTime<Utc>,
+    /// ランキングの対象となったプレーヤーの総数。
+    pub total_player_count: u64,
+    /// 上位のプレーヤー達。順位の昇順に並んでいる。
+    pub top_players: Vec<RankedPlayer<Value>>,
+}
+
+/// `player_values` を値の降順に並べ、上位 `limit` 人に順位を付けて返す。
+///
+/// 同じ値を持つプレーヤー同士は UUID の昇順に並べるため、
+/// 同順位のプレーヤーが `limit` 人目をまたぐ場合にどのプレーヤーが含まれるかは決定的である。
+pub fn rank_players_by_value<Value: Ord + Clone>(
+    player_values: &HashMap<Player, Value>,
+    limit: usize,
+) -> Vec<RankedPlayer<Value>> {
+    let mut sorted_player_values = player_values.iter().collect::<Vec<_>>();
+    sorted_player_values.sort_unstable_by(|(player_a, value_a), (player_b, value_b)| {
+        value_b.cmp(value_a).then_with(|| player_a.cmp(player_b))
+    });
+
+    let mut ranked_players: Vec<RankedPlayer<Value>> =
+        Vec::with_capacity(limit.min(player_values.len()));
+    for (index, (player, value)) in sorted_player_values.into_iter().take(limit).enumerate() {
+        let rank = match ranked_players.last() {
+            Some(previous) if &previous.value == value => previous.rank,
+            _ => index as u64 + 1,
+        };
+
+        ranked_players.push(RankedPlayer {
+            rank,
+            player: *player,
+            value: value.clone(),
+        });
+    }
+
+    ranked_players
+}
+
+impl<Stats: Ord + Clone> StatsSnapshot<Stats> {
+    /// スナップショット内の上位 `limit` 人のランキングを計算する。
+    pub fn ranking(&self, limit: usize) -> Ranking<Stats> {
+        Ranking {
+            utc_timestamp: self.utc_timestamp,
+            total_player_count: self.len() as u64,
+            top_players: rank_players_by_value(&self.player_stats, limit),
+        }
+    }
+}
+
+impl<Stats: Ord> StatsSnapshot<Stats> {
+    /// スナップショット内での `player` の順位を、 [`RankedPlayer::rank`] と同じ規則で計算する。
+    /// `player` がスナップショットに含まれていない場合は `None` を返す。
+    pub fn rank_of(&self, player: &Player) -> Option<u64> {
+        let value = self.player_stats.get(player)?;
+        let greater_value_count = self
+            .player_stats
+            .values()
+            .filter(|other_value| *other_value > value)
+            .count();
+
+        Some(greater_value_count as u64 + 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::BreakCount;
+    use crate::test_fixtures::{player, snapshot};
+
+    #[test]
+    fn tied_players_share_a_rank_and_the_next_rank_is_skipped() {
+        let snapshot = snapshot(0, &[(1, 10), (2, 20), (3, 30), (4, 20)]);
+
+        let ranking = snapshot.ranking(10);
+
+        assert_eq!(ranking.total_player_count, 4);
+        assert_eq!(
+            ranking
+                .top_players
+                .into_iter()
+                .map(|ranked_player| (
+                    ranked_player.rank,
+                    ranked_player.player,
+                    ranked_player.value
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                (1, player(3), BreakCount(30)),
+                (2, player(2), BreakCount(20)),
+                (2, player(4), BreakCount(20)),
+                (4, player(1), BreakCount(10)),
+            ]
+        );
+    }
+
+    #[test]
+    fn limit_cutting_through_ties_keeps_players_with_smaller_uuids() {
+        let snapshot = snapshot(0, &[(1, 30), (2, 20), (3, 20), (4, 20)]);
+
+        let ranking = snapshot.ranking(3);
+
+        assert_eq!(ranking.total_player_count, 4);
+        assert_eq!(
+            ranking
+                .top_players
+                .iter()
+                .map(|ranked_player| (ranked_player.rank, ranked_player.player))
+                .collect::<Vec<_>>(),
+            vec![(1, player(1)), (2, player(2)), (2, player(3))]
+        );
+    }
+
+    #[test]
+    fn rank_of_agrees_with_the_ranking() {
+        let snapshot = snapshot(0, &[(1, 10), (2, 20), (3, 30), (4, 20)]);
+
+        for ranked_player in snapshot.ranking(10).top_players {
+            assert_eq!(
+                snapshot.rank_of(&ranked_player.player),
+                Some(ranked_player.rank)
+            );
+        }
+        assert_eq!(snapshot.rank_of(&player(5)), None);
+    }
+}
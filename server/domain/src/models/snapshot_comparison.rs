@@ -0,0 +1,107 @@
+use chrono::{DateTime, Utc};
+
+use super::{Player, StatsSnapshot};
+
+/// 二つのスナップショットの間での、あるプレーヤーの統計量の変化。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlayerStatsChange<Stats> {
+    pub player: Player,
+    /// 古い方のスナップショットでの統計量。プレーヤーが含まれていなかった場合は `None` となる。
+    pub old_stats: Option<Stats>,
+    /// 新しい方のスナップショットでの統計量。プレーヤーが含まれなくなった場合は `None` となる。
+    pub new_stats: Option<Stats>,
+}
+
+/// 二つのスナップショットを比較した結果。
+#[derive(Debug, Clone)]
+pub struct SnapshotComparison<Stats> {
+    /// 古い方のスナップショットの時刻。古い方のスナップショットが存在しなかった場合は `None` となる。
+    pub old_utc_timestamp: Option<DateTime<Utc>>,
+    /// 新しい方のスナップショットの時刻。
+    pub new_utc_timestamp: DateTime<Utc>,
+    /// 統計量が変化したか、現れたか、含まれなくなったプレーヤーの変化。 UUID の昇順に並んでいる。
+    pub changes: Vec<PlayerStatsChange<Stats>>,
+}
+
+impl<Stats: Clone + Eq> StatsSnapshot<Stats> {
+    /// `older` からこのスナップショットまでの、各プレーヤーの統計量の変化を計算する。
+    ///
+    /// `older` が `None` の場合は、このスナップショットに含まれるすべてのプレーヤーが新たに現れたものとして扱う。
+    pub fn compare_since(&self, older: Option<&StatsSnapshot<Stats>>) -> SnapshotComparison<Stats> {
+        let changed_or_appeared = self.player_stats.iter().filter_map(|(player, new_stats)| {
+            let old_stats = older.and_then(|older| older.player_stats.get(player));
+
+            (old_stats != Some(new_stats)).then(|| PlayerStatsChange {
+                player: *player,
+                old_stats: old_stats.cloned(),
+                new_stats: Some(new_stats.clone()),
+            })
+        });
+
+        let disappeared = older
+            .into_iter()
+            .flat_map(|older| older.player_stats.iter())
+            .filter(|(player, _)| !self.player_stats.contains_key(player))
+            .map(|(player, old_stats)| PlayerStatsChange {
+                player: *player,
+                old_stats: Some(old_stats.clone()),
+                new_stats: None,
+            });
+
+        let mut changes = changed_or_appeared.chain(disappeared).collect::<Vec<_>>();
+        changes.sort_unstable_by_key(|change| change.player);
+
+        SnapshotComparison {
+            old_utc_timestamp: older.map(|older| older.utc_timestamp),
+            new_utc_timestamp: self.utc_timestamp,
+            changes,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PlayerStatsChange;
+    use crate::models::BreakCount;
+    use crate::test_fixtures::{player, snapshot};
+
+    fn change(n: u64, old: Option<u64>, new: Option<u64>) -> PlayerStatsChange<BreakCount> {
+        PlayerStatsChange {
+            player: player(n),
+            old_stats: old.map(BreakCount),
+            new_stats: new.map(BreakCount),
+        }
+    }
+
+    #[test]
+    fn disappeared_players_are_reported_without_new_stats() {
+        let older = snapshot(0, &[(1, 10), (2, 20), (3, 30)]);
+        let newer = snapshot(10, &[(1, 10), (3, 35), (4, 40)]);
+
+        let comparison = newer.compare_since(Some(&older));
+
+        assert_eq!(comparison.old_utc_timestamp, Some(older.utc_timestamp));
+        assert_eq!(comparison.new_utc_timestamp, newer.utc_timestamp);
+        assert_eq!(
+            comparison.changes,
+            vec![
+                change(2, Some(20), None),
+                change(3, Some(30), Some(35)),
+                change(4, None, Some(40)),
+            ]
+        );
+    }
+
+    #[test]
+    fn every_player_appears_when_there_is_no_older_snapshot() {
+        let newer = snapshot(10, &[(2, 20), (1, 10)]);
+
+        let comparison = newer.compare_since(None);
+
+        assert_eq!(comparison.old_utc_timestamp, None);
+        assert_eq!(
+            comparison.changes,
+            vec![change(1, None, Some(10)), change(2, None, Some(20))]
+        );
+    }
+}
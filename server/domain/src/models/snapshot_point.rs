@@ -0,0 +1,102 @@
+use std::collections::{HashMap, HashSet};
+
+use chrono::{DateTime, Utc};
+
+use super::Player;
+
+/// 統計量スナップショット、または統計量差分が記録されたデータ点の ID。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SnapshotPointId {
+    Full(u64),
+    Diff(u64),
+}
+
+/// データ点に関する、統計量を含まない情報。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SnapshotPointMetadata {
+    Full {
+        id: u64,
+        utc_timestamp: DateTime<Utc>,
+    },
+    Diff {
+        id: u64,
+        utc_timestamp: DateTime<Utc>,
+        root_full_snapshot_point_id: u64,
+        previous_diff_point_id: Option<u64>,
+        /// このデータ点に記録されている差分レコードの数。
+        diff_record_count: u64,
+    },
+}
+
+impl SnapshotPointMetadata {
+    pub fn id(&self) -> SnapshotPointId {
+        match self {
+            Self::Full { id, .. } => SnapshotPointId::Full(*id),
+            Self::Diff { id, .. } => SnapshotPointId::Diff(*id),
+        }
+    }
+
+    pub fn utc_timestamp(&self) -> DateTime<Utc> {
+        match self {
+            Self::Full { utc_timestamp, .. } | Self::Diff { utc_timestamp, .. } => *utc_timestamp,
+        }
+    }
+}
+
+/// 種類ごとに、それまでに記録されたデータ点の ID のうち最大のもの。
+///
+/// データ点の ID は記録された順に大きくなるため、これより大きな ID を持つデータ点は、
+/// 記録されている時刻によらず、これを得た後に記録されたものとなる。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LastSnapshotPointIds {
+    /// 一つも記録されていなければ `None`。
+    pub full: Option<u64>,
+    /// 一つも記録されていなければ `None`。
+    pub diff: Option<u64>,
+}
+
+impl LastSnapshotPointIds {
+    /// `point` を取得済みのものとして、ID を `point` のものまで進める。
+    pub fn advance_to(&mut self, point: SnapshotPointId) {
+        let (last_id, id) = match point {
+            SnapshotPointId::Full(id) => (&mut self.full, id),
+            SnapshotPointId::Diff(id) => (&mut self.diff, id),
+        };
+
+        *last_id = Some(last_id.map_or(id, |last_id| last_id.max(id)));
+    }
+}
+
+/// 記録されたデータ点と、そのデータ点に記録されている統計量のレコード。
+#[derive(Debug, Clone)]
+pub struct RecordedSnapshotPoint<Stats> {
+    pub metadata: SnapshotPointMetadata,
+    /// diff point において、直前のデータ点から統計量が変化したプレーヤーの新しい統計量。
+    /// full snapshot point ではスナップショット全体が非常に大きくなりうるため、常に空となる。
+    pub player_stats: HashMap<Player, Stats>,
+    /// diff point において、直前のデータ点から消えたプレーヤー。
+    /// full snapshot point では常に空となる。
+    pub removed_players: HashSet<Player>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LastSnapshotPointIds, SnapshotPointId};
+
+    #[test]
+    fn advancing_keeps_the_largest_id_of_each_kind() {
+        let mut last_ids = LastSnapshotPointIds::default();
+
+        last_ids.advance_to(SnapshotPointId::Diff(5));
+        last_ids.advance_to(SnapshotPointId::Full(2));
+        last_ids.advance_to(SnapshotPointId::Diff(3));
+
+        assert_eq!(
+            last_ids,
+            LastSnapshotPointIds {
+                full: Some(2),
+                diff: Some(5),
+            }
+        );
+    }
+}
@@ -0,0 +1,162 @@
+use anyhow::ensure;
+use chrono::{DateTime, Utc};
+
+use super::{NumericStats, StatsSnapshot};
+use crate::errors::InvalidArgumentError;
+
+/// 統計量の分布における、ある百分位数。
+#[derive(Debug, Clone, PartialEq)]
+pub struct Percentile {
+    /// 0 以上 100 以下の百分率。
+    pub percentage: f64,
+    /// 最近順位法 (nearest-rank method) によって求めた、スナップショットに実際に含まれる値。
+    pub value: u64,
+}
+
+/// スナップショットに含まれるプレーヤー達の統計量を要約したもの。
+///
+/// 最小値などの値は、スナップショットにプレーヤーが一人も含まれていない場合は `None` となる。
+#[derive(Debug, Clone, PartialEq)]
+pub struct SnapshotSummary {
+    pub utc_timestamp: DateTime<Utc>,
+    pub player_count: u64,
+    /// 統計量の総和。 `u64` の範囲を超える場合は `u64::MAX` に飽和する。
+    pub sum: u64,
+    pub mean: Option<f64>,
+    /// プレーヤー数が偶数の場合は、中央の二つの値の平均となる。
+    pub median: Option<f64>,
+    pub min: Option<u64>,
+    pub max: Option<u64>,
+    /// 要求された順に並んだ百分位数。プレーヤーが一人も含まれていない場合は空となる。
+    pub percentiles: Vec<Percentile>,
+}
+
+impl<Stats: NumericStats> StatsSnapshot<Stats> {
+    /// スナップショットの統計量を要約する。 `percentages` のそれぞれについて百分位数を求める。
+    /// 0 以上 100 以下でない百分率が含まれる場合は [`InvalidArgumentError`] となる。
+    pub fn summarize(&self, percentages: &[f64]) -> anyhow::Result<SnapshotSummary> {
+        for percentage in percentages {
+            ensure!(
+                (0.0..=100.0).contains(percentage),
+                InvalidArgumentError(format!(
+                    "percentage must be between 0 and 100, but got {percentage}"
+                ))
+            );
+        }
+
+        let mut values = self
+            .player_stats
+            .values()
+            .map(NumericStats::raw_value)
+            .collect::<Vec<_>>();
+        values.sort_unstable();
+
+        let player_count = values.len();
+        let exact_sum = values.iter().map(|value| u128::from(*value)).sum::<u128>();
+
+        let median = match player_count {
+            0 => None,
+            count if count % 2 == 1 => Some(values[count / 2] as f64),
+            count => Some((values[count / 2 - 1] as f64 + values[count / 2] as f64) / 2.0),
+        };
+
+        let percentiles = if values.is_empty() {
+            Vec::new()
+        } else {
+            percentages
+                .iter()
+                .map(|percentage| {
+                    let rank = (percentage / 100.0 * player_count as f64).ceil() as usize;
+
+                    Percentile {
+                        percentage: *percentage,
+                        value: values[rank.clamp(1, player_count) - 1],
+                    }
+                })
+                .collect()
+        };
+
+        Ok(SnapshotSummary {
+            utc_timestamp: self.utc_timestamp,
+            player_count: player_count as u64,
+            sum: u64::try_from(exact_sum).unwrap_or(u64::MAX),
+            mean: (player_count > 0).then(|| exact_sum as f64 / player_count as f64),
+            median,
+            min: values.first().copied(),
+            max: values.last().copied(),
+            percentiles,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SnapshotSummary;
+    use crate::errors::InvalidArgumentError;
+    use crate::test_fixtures::snapshot;
+
+    fn percentile_values(summary: &SnapshotSummary) -> Vec<u64> {
+        summary
+            .percentiles
+            .iter()
+            .map(|percentile| percentile.value)
+            .collect()
+    }
+
+    #[test]
+    fn percentiles_are_values_at_the_nearest_rank() {
+        let summary = snapshot(0, &[(1, 40), (2, 10), (3, 30), (4, 20)])
+            .summarize(&[0.0, 25.0, 50.0, 51.0, 90.0, 100.0])
+            .unwrap();
+
+        assert_eq!(percentile_values(&summary), vec![10, 10, 20, 30, 40, 40]);
+    }
+
+    #[test]
+    fn median_is_the_middle_value_for_an_odd_player_count() {
+        let summary = snapshot(0, &[(1, 5), (2, 1), (3, 3)])
+            .summarize(&[])
+            .unwrap();
+
+        assert_eq!(summary.median, Some(3.0));
+        assert_eq!(summary.mean, Some(3.0));
+        assert_eq!((summary.min, summary.max), (Some(1), Some(5)));
+    }
+
+    #[test]
+    fn median_is_the_mean_of_the_middle_two_values_for_an_even_player_count() {
+        let summary = snapshot(0, &[(1, 40), (2, 10), (3, 30), (4, 20)])
+            .summarize(&[])
+            .unwrap();
+
+        assert_eq!(summary.median, Some(25.0));
+    }
+
+    #[test]
+    fn empty_snapshot_has_no_median_or_percentiles() {
+        let summary = snapshot(0, &[]).summarize(&[50.0]).unwrap();
+
+        assert_eq!(summary.player_count, 0);
+        assert_eq!(summary.sum, 0);
+        assert_eq!(summary.median, None);
+        assert!(summary.percentiles.is_empty());
+    }
+
+    #[test]
+    fn sum_saturates_at_u64_max() {
+        let summary = snapshot(0, &[(1, u64::MAX), (2, 1)])
+            .summarize(&[])
+            .unwrap();
+
+        assert_eq!(summary.sum, u64::MAX);
+    }
+
+    #[test]
+    fn percentages_out_of_range_are_rejected() {
+        for percentage in [100.5, -1.0, f64::NAN] {
+            let error = snapshot(0, &[(1, 1)]).summarize(&[percentage]).unwrap_err();
+
+            assert!(error.is::<InvalidArgumentError>());
+        }
+    }
+}
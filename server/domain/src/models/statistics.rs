@@ -1,13 +1,38 @@
 use std::fmt::Debug;
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub struct BreakCount(pub u64);
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub struct BuildCount(pub u64);
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub struct PlayTicks(pub u64);
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub struct VoteCount(pub u64);
+
+/// 非負整数値によって表現される統計量。
+pub trait NumericStats: Sized {
+    fn from_raw_value(raw_value: u64) -> Self;
+    fn raw_value(&self) -> u64;
+}
+
+macro_rules! impl_numeric_stats {
+    ($stats_type:ident) => {
+        impl NumericStats for $stats_type {
+            fn from_raw_value(raw_value: u64) -> Self {
+                $stats_type(raw_value)
+            }
+
+            fn raw_value(&self) -> u64 {
+                self.0
+            }
+        }
+    };
+}
+
+impl_numeric_stats!(BreakCount);
+impl_numeric_stats!(BuildCount);
+impl_numeric_stats!(PlayTicks);
+impl_numeric_stats!(VoteCount);
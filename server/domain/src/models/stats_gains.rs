@@ -0,0 +1,94 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+
+use super::{rank_players_by_value, NumericStats, Player, RankedPlayer, StatsSnapshot};
+
+/// ある期間における、各プレーヤーの統計量の増加量。
+#[derive(Debug, Clone)]
+pub struct StatsGains {
+    /// 期間の始点として用いたスナップショットの時刻。
+    /// 始点となるスナップショットが存在しなかった場合は `None` となる。
+    pub from_utc_timestamp: Option<DateTime<Utc>>,
+    /// 期間の終点として用いたスナップショットの時刻。
+    pub to_utc_timestamp: DateTime<Utc>,
+    /// 期間中に統計量が増加したプレーヤーの、統計量の増加量。
+    /// 統計量が増加しなかったプレーヤーは含まれない。
+    pub player_gains: HashMap<Player, u64>,
+}
+
+impl StatsGains {
+    /// 増加量が大きい順に、上位 `limit` 人に順位を付けて返す。
+    pub fn top_players(&self, limit: usize) -> Vec<RankedPlayer<u64>> {
+        rank_players_by_value(&self.player_gains, limit)
+    }
+}
+
+impl<Stats: NumericStats> StatsSnapshot<Stats> {
+    /// `earlier` からこのスナップショットまでの、各プレーヤーの統計量の増加量を計算する。
+    ///
+    /// `earlier` に含まれないプレーヤーは `earlier` の時点で統計量が 0 であったものとして扱う。
+    /// 統計量が減少したプレーヤーは、増加量が 0 であったものとして扱う。
+    pub fn gains_since(&self, earlier: Option<&StatsSnapshot<Stats>>) -> StatsGains {
+        let player_gains = self
+            .player_stats
+            .iter()
+            .filter_map(|(player, stats)| {
+                let earlier_value = earlier
+                    .and_then(|earlier| earlier.player_stats.get(player))
+                    .map_or(0, NumericStats::raw_value);
+                let gain = stats.raw_value().saturating_sub(earlier_value);
+
+                (gain > 0).then_some((*player, gain))
+            })
+            .collect();
+
+        StatsGains {
+            from_utc_timestamp: earlier.map(|earlier| earlier.utc_timestamp),
+            to_utc_timestamp: self.utc_timestamp,
+            player_gains,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use crate::test_fixtures::{player, snapshot};
+
+    #[test]
+    fn players_absent_from_the_earlier_snapshot_gain_their_whole_value() {
+        let earlier = snapshot(0, &[(1, 10)]);
+        let later = snapshot(10, &[(1, 15), (2, 30)]);
+
+        let gains = later.gains_since(Some(&earlier));
+
+        assert_eq!(gains.from_utc_timestamp, Some(earlier.utc_timestamp));
+        assert_eq!(gains.to_utc_timestamp, later.utc_timestamp);
+        assert_eq!(
+            gains.player_gains,
+            HashMap::from([(player(1), 5), (player(2), 30)])
+        );
+    }
+
+    #[test]
+    fn without_an_earlier_snapshot_every_value_is_a_gain() {
+        let later = snapshot(10, &[(1, 15), (2, 0)]);
+
+        let gains = later.gains_since(None);
+
+        assert_eq!(gains.from_utc_timestamp, None);
+        assert_eq!(gains.player_gains, HashMap::from([(player(1), 15)]));
+    }
+
+    #[test]
+    fn players_whose_stats_did_not_increase_are_excluded() {
+        let earlier = snapshot(0, &[(1, 10), (2, 20), (3, 30)]);
+        let later = snapshot(10, &[(1, 5), (2, 20), (3, 31)]);
+
+        let gains = later.gains_since(Some(&earlier));
+
+        assert_eq!(gains.player_gains, HashMap::from([(player(3), 1)]));
+    }
+}
@@ -25,3 +25,90 @@ impl<Stats> Debug for StatsSnapshot<Stats> {
             .finish()
     }
 }
+
+/// 統計量スナップショットを、スナップショット全体を一度にメモリ上に構築することなく、
+/// 一定数のプレーヤーごとに分割して取り出すためのもの。
+pub struct StatsSnapshotChunks<PlayerStats> {
+    pub utc_timestamp: DateTime<Utc>,
+    /// スナップショットに含まれるプレーヤーの総数。
+    pub player_stats_count: usize,
+    /// 高々 `chunk_size` 人分の統計量を持つ `Vec` を順に返すイテレータ。
+    /// スナップショットが空であっても、少なくとも一つの (空の) `Vec` を返す。
+    pub chunks: Box<dyn Iterator<Item = Vec<(Player, PlayerStats)>> + Send>,
+}
+
+impl<Stats: 'static> StatsSnapshotChunks<Stats> {
+    /// # Panics
+    ///
+    /// `chunk_size` が 0 のとき panic する。
+    pub fn new(
+        utc_timestamp: DateTime<Utc>,
+        player_stats_count: usize,
+        mut player_stats: impl Iterator<Item = (Player, Stats)> + Send + 'static,
+        chunk_size: usize,
+    ) -> Self {
+        assert!(chunk_size > 0, "chunk_size must be positive");
+
+        let mut is_first_chunk = true;
+        let chunks = std::iter::from_fn(move || {
+            let chunk = player_stats.by_ref().take(chunk_size).collect::<Vec<_>>();
+
+            if chunk.is_empty() && !is_first_chunk {
+                None
+            } else {
+                is_first_chunk = false;
+                Some(chunk)
+            }
+        });
+
+        Self {
+            utc_timestamp,
+            player_stats_count,
+            chunks: Box::new(chunks),
+        }
+    }
+}
+
+impl<Stats> Debug for StatsSnapshotChunks<Stats> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("StatsSnapshotChunks")
+            .field("player_stats_count", &self.player_stats_count)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::{snapshot, timestamp};
+
+    fn chunk_sizes(player_count: u64, chunk_size: usize) -> Vec<usize> {
+        let player_values = (0..player_count).map(|n| (n, n)).collect::<Vec<_>>();
+        let snapshot = snapshot(0, &player_values);
+
+        StatsSnapshotChunks::new(
+            timestamp(0),
+            snapshot.len(),
+            snapshot.player_stats.into_iter(),
+            chunk_size,
+        )
+        .chunks
+        .map(|chunk| chunk.len())
+        .collect()
+    }
+
+    #[test]
+    fn empty_snapshot_yields_a_single_empty_chunk() {
+        assert_eq!(chunk_sizes(0, 3), vec![0]);
+    }
+
+    #[test]
+    fn chunks_hold_at_most_chunk_size_players() {
+        assert_eq!(chunk_sizes(7, 3), vec![3, 3, 1]);
+    }
+
+    #[test]
+    fn no_trailing_empty_chunk_is_yielded_when_players_divide_evenly() {
+        assert_eq!(chunk_sizes(6, 3), vec![3, 3]);
+    }
+}
@@ -0,0 +1,145 @@
+use std::collections::HashMap;
+
+use anyhow::ensure;
+use chrono::{DateTime, Duration, Utc};
+
+use super::Player;
+
+/// `from` から `to` までの期間を、 `interval` ごとの時刻で標本化する方法。
+#[derive(Debug, Clone, Copy)]
+pub struct TimeSeriesSampling {
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+    interval: Duration,
+}
+
+impl TimeSeriesSampling {
+    /// 一つの時系列に含めることができる標本の最大数。
+    pub const MAX_SAMPLE_COUNT: i64 = 10_000;
+
+    pub fn new(from: DateTime<Utc>, to: DateTime<Utc>, interval: Duration) -> anyhow::Result<Self> {
+        ensure!(interval > Duration::zero(), "interval must be positive");
+        ensure!(from <= to, "`from` must not be later than `to`");
+
+        let sample_count = (to - from).num_milliseconds() / interval.num_milliseconds().max(1) + 1;
+        ensure!(
+            sample_count <= Self::MAX_SAMPLE_COUNT,
+            "too many samples ({sample_count}); at most {} samples are allowed",
+            Self::MAX_SAMPLE_COUNT
+        );
+
+        Ok(Self { from, to, interval })
+    }
+
+    pub const fn from(&self) -> DateTime<Utc> {
+        self.from
+    }
+
+    pub const fn to(&self) -> DateTime<Utc> {
+        self.to
+    }
+
+    /// `from` 以降 `to` 以前の、 `from` から `interval` の整数倍だけ離れた時刻を昇順に返す。
+    pub fn sample_timestamps(&self) -> impl Iterator<Item = DateTime<Utc>> {
+        let Self { from, to, interval } = *self;
+
+        std::iter::successors(Some(from), move |timestamp| Some(*timestamp + interval))
+            .take_while(move |timestamp| *timestamp <= to)
+    }
+}
+
+/// 標本化されたある時刻における、プレーヤー達の統計量。
+#[derive(Debug, Clone)]
+pub struct ResampledStats<Stats> {
+    /// 標本の時刻。
+    pub sample_utc_timestamp: DateTime<Utc>,
+    /// 標本の値として用いた、標本の時刻以前で最も新しいデータ点の時刻。
+    /// そのようなデータ点が存在しない場合は `None` となる。
+    pub snapshot_utc_timestamp: Option<DateTime<Utc>>,
+    /// データ点に統計量が含まれていたプレーヤーの統計量。
+    pub player_stats: HashMap<Player, Stats>,
+}
+
+/// 時系列を構成する時刻の選び方。
+#[derive(Debug, Clone, Copy)]
+pub enum TimeSeriesPoints {
+    /// `from` 以降 `to` 以前に記録されたすべてのデータ点の時刻。
+    Recorded {
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    },
+    /// 一定間隔で標本化された時刻。
+    Sampled(TimeSeriesSampling),
+}
+
+/// ある時刻における、あるプレーヤーの統計量の順位。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TimestampedRank {
+    /// 時系列上の時刻。
+    pub utc_timestamp: DateTime<Utc>,
+    /// 順位の計算に用いたデータ点の時刻。
+    /// そのようなデータ点が存在しない場合は `None` となる。
+    pub snapshot_utc_timestamp: Option<DateTime<Utc>>,
+    /// 1 から始まる順位。プレーヤーがデータ点に含まれていない場合は `None` となる。
+    pub rank: Option<u64>,
+    /// データ点に含まれていたプレーヤーの総数。
+    pub total_ranked_player_count: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+
+    use super::TimeSeriesSampling;
+    use crate::test_fixtures::timestamp;
+
+    #[test]
+    fn sampling_up_to_max_sample_count_is_allowed() {
+        let last_sample_offset = TimeSeriesSampling::MAX_SAMPLE_COUNT - 1;
+        let sampling = TimeSeriesSampling::new(
+            timestamp(0),
+            timestamp(last_sample_offset),
+            Duration::minutes(1),
+        )
+        .unwrap();
+
+        assert_eq!(
+            sampling.sample_timestamps().count() as i64,
+            TimeSeriesSampling::MAX_SAMPLE_COUNT
+        );
+        assert_eq!(
+            sampling.sample_timestamps().last(),
+            Some(timestamp(last_sample_offset))
+        );
+    }
+
+    #[test]
+    fn sampling_beyond_max_sample_count_is_rejected() {
+        let sampling = TimeSeriesSampling::new(
+            timestamp(0),
+            timestamp(TimeSeriesSampling::MAX_SAMPLE_COUNT),
+            Duration::minutes(1),
+        );
+
+        assert!(sampling.is_err());
+    }
+
+    #[test]
+    fn samples_do_not_go_past_to() {
+        let sampling =
+            TimeSeriesSampling::new(timestamp(0), timestamp(25), Duration::minutes(10)).unwrap();
+
+        assert_eq!(
+            sampling.sample_timestamps().collect::<Vec<_>>(),
+            vec![timestamp(0), timestamp(10), timestamp(20)]
+        );
+    }
+
+    #[test]
+    fn invalid_samplings_are_rejected() {
+        assert!(TimeSeriesSampling::new(timestamp(0), timestamp(10), Duration::zero()).is_err());
+        assert!(
+            TimeSeriesSampling::new(timestamp(10), timestamp(0), Duration::minutes(1)).is_err()
+        );
+    }
+}
@@ -0,0 +1,8 @@
+use chrono::{DateTime, Utc};
+
+/// 特定の時刻に記録された、あるプレーヤーの統計量。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TimestampedStats<Stats> {
+    pub utc_timestamp: DateTime<Utc>,
+    pub stats: Stats,
+}
@@ -1,5 +1,7 @@
+mod player_all_timed_stats_repository;
 mod player_stats_repository;
 mod player_timed_stats_repository;
 
+pub use player_all_timed_stats_repository::*;
 pub use player_stats_repository::*;
 pub use player_timed_stats_repository::*;
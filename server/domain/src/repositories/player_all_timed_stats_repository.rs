@@ -0,0 +1,12 @@
+use crate::models::AllStatsSnapshot;
+use crate::repositories::TimeBasedSnapshotSearchCondition;
+
+#[async_trait::async_trait]
+pub trait PlayerAllTimedStatsRepository {
+    /// すべての種類の統計量について `condition` に合致するスナップショットを検索し、
+    /// プレーヤーごとにまとめて返す。
+    async fn search_all_stats_snapshot(
+        &self,
+        condition: TimeBasedSnapshotSearchCondition,
+    ) -> anyhow::Result<AllStatsSnapshot>;
+}
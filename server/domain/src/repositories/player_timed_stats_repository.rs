@@ -1,18 +1,130 @@
-use crate::models::StatsSnapshot;
+use std::collections::{HashMap, HashSet};
+
+use crate::models::{
+    LastSnapshotPointIds, Player, ResampledStats, SnapshotPointId, SnapshotPointMetadata,
+    StatsSnapshot, StatsSnapshotChunks, TimeSeriesPoints, TimeSeriesSampling, TimestampedRank,
+    TimestampedStats,
+};
 use chrono::{DateTime, Utc};
 
+/// データ点の検索条件。
+///
+/// 条件に等しく合致するデータ点が複数ある場合は、 diff point よりも full snapshot point を、
+/// 同じ種類のデータ点の中では `OldestAfter` であれば ID の最も小さいものを、
+/// それ以外であれば ID の最も大きいものを採用する。
 #[derive(Debug, Clone, Copy)]
 pub enum TimeBasedSnapshotSearchCondition {
     NewestBefore(DateTime<Utc>),
     OldestAfter(DateTime<Utc>),
+    /// 指定された時刻に最も近い時刻に記録されたものを検索する。
+    /// 前後に等しく近いものがある場合は、より古い方を採用する。
+    Nearest(DateTime<Utc>),
+    /// 指定された時刻ちょうどに記録されたものを検索する。
+    Exact(DateTime<Utc>),
+    /// 指定された ID を持つデータ点を検索する。
+    AtPoint(SnapshotPointId),
+}
+
+impl TimeBasedSnapshotSearchCondition {
+    /// 条件が基準とする時刻。データ点を ID で指定する条件の場合は `None` となる。
+    pub const fn reference_timestamp(&self) -> Option<DateTime<Utc>> {
+        match self {
+            Self::NewestBefore(timestamp)
+            | Self::OldestAfter(timestamp)
+            | Self::Nearest(timestamp)
+            | Self::Exact(timestamp) => Some(*timestamp),
+            Self::AtPoint(_) => None,
+        }
+    }
 }
 
 #[async_trait::async_trait]
 pub trait PlayerTimedStatsRepository<PlayerStats> {
+    /// 最新の統計量としてスナップショットを記録する。
+    /// `snapshot` の時刻以降に記録されたデータ点が既に存在する場合はエラーとなる。
     async fn record_snapshot(&self, snapshot: StatsSnapshot<PlayerStats>) -> anyhow::Result<()>;
 
+    /// 過去の時刻に取得されたスナップショットを、既に記録されているデータ点を変更することなく記録する。
+    /// `snapshot` の時刻ちょうどに記録されたデータ点が既に存在する場合はエラーとなる。
+    async fn backfill_snapshot(&self, snapshot: StatsSnapshot<PlayerStats>) -> anyhow::Result<()>;
+
     async fn search_snapshot(
         &self,
         condition: TimeBasedSnapshotSearchCondition,
     ) -> anyhow::Result<Option<StatsSnapshot<PlayerStats>>>;
+
+    /// `search_snapshot` と同様にスナップショットを検索するが、
+    /// スナップショットを高々 `chunk_size` 人分ずつに分割して返す。
+    async fn search_snapshot_in_chunks(
+        &self,
+        condition: TimeBasedSnapshotSearchCondition,
+        chunk_size: usize,
+    ) -> anyhow::Result<Option<StatsSnapshotChunks<PlayerStats>>>;
+
+    /// `search_snapshot` と同様にスナップショットを検索するが、
+    /// `players` に含まれるプレーヤーの統計量のみを、スナップショット全体を復元することなく返す。
+    async fn search_snapshot_of_players(
+        &self,
+        players: HashSet<Player>,
+        condition: TimeBasedSnapshotSearchCondition,
+    ) -> anyhow::Result<Option<StatsSnapshot<PlayerStats>>>;
+
+    /// `condition` に合致するデータ点での `player` の統計量を、スナップショット全体を復元することなく返す。
+    /// 条件に合致するデータ点が存在しないか、データ点が `player` の統計量を含まない場合は `None` を返す。
+    async fn search_player_stats(
+        &self,
+        player: Player,
+        condition: TimeBasedSnapshotSearchCondition,
+    ) -> anyhow::Result<Option<TimestampedStats<PlayerStats>>>;
+
+    /// `from` 以降 `to` 以前に記録されたすべてのデータ点での `player` の統計量を、時刻の昇順に返す。
+    /// `player` の統計量を含まないデータ点は結果に含まれない。
+    async fn search_player_stats_history(
+        &self,
+        player: Player,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> anyhow::Result<Vec<TimestampedStats<PlayerStats>>>;
+
+    /// `sampling` が定める各時刻について、その時刻以前で最も新しいデータ点での `players` の統計量を、
+    /// 時刻の昇順に返す。
+    async fn search_resampled_player_stats(
+        &self,
+        players: HashSet<Player>,
+        sampling: TimeSeriesSampling,
+    ) -> anyhow::Result<Vec<ResampledStats<PlayerStats>>>;
+
+    /// `points` が定める各時刻について、その時刻でのデータ点における `player` の順位と
+    /// 順位付けされたプレーヤーの総数を、時刻の昇順に返す。
+    async fn search_player_rank_history(
+        &self,
+        player: Player,
+        points: TimeSeriesPoints,
+    ) -> anyhow::Result<Vec<TimestampedRank>>;
+
+    /// `from` 以降 `to` 以前に記録されたすべてのデータ点の情報を、時刻の昇順に返す。
+    async fn list_snapshot_points(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> anyhow::Result<Vec<SnapshotPointMetadata>>;
+
+    /// 現在記録されているデータ点の ID のうち、種類ごとに最大のものを返す。
+    async fn last_snapshot_point_ids(&self) -> anyhow::Result<LastSnapshotPointIds>;
+
+    /// `last_ids` より大きな ID を持つデータ点、すなわち `last_ids` を得た後に記録されたデータ点の情報を、
+    /// full snapshot point、 diff point の順に、それぞれ ID の昇順で返す。
+    async fn list_snapshot_points_recorded_after(
+        &self,
+        last_ids: LastSnapshotPointIds,
+    ) -> anyhow::Result<Vec<SnapshotPointMetadata>>;
+
+    /// `point` に記録されている統計量のレコードを、スナップショットを復元することなく返す。
+    /// full snapshot point であればすべてのプレーヤーの統計量を、
+    /// diff point であれば統計量が変化したプレーヤーの新しい統計量のみを返す。
+    /// diff point において、直前のデータ点から消えたプレーヤーは `None` に対応付けられる。
+    async fn search_records_at_snapshot_point(
+        &self,
+        point: SnapshotPointId,
+    ) -> anyhow::Result<HashMap<Player, Option<PlayerStats>>>;
 }
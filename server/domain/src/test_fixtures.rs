@@ -0,0 +1,35 @@
+//! テストで用いるプレーヤーやスナップショットを簡潔に作るための関数。
+
+use chrono::{DateTime, Duration, Utc};
+
+use crate::models::{BreakCount, Player, PlayerUuidString, StatsSnapshot};
+
+/// `n` から一意に定まるプレーヤー。 UUID の順序は `n` の順序と一致する。
+///
+/// # Panics
+///
+/// `n` が 12 桁に収まらない場合は panic する。
+pub fn player(n: u64) -> Player {
+    let uuid = format!("00000000-0000-0000-0000-{n:012}");
+
+    Player {
+        uuid: PlayerUuidString::from_string(&uuid).expect("n must fit in 12 digits"),
+    }
+}
+
+/// 基準となる時刻から `minute` 分後の時刻。
+pub fn timestamp(minute: i64) -> DateTime<Utc> {
+    DateTime::UNIX_EPOCH + Duration::minutes(minute)
+}
+
+/// `timestamp(minute)` の時刻のスナップショットで、 `(n, value)` のそれぞれについて
+/// `player(n)` の統計量が `value` であるもの。
+pub fn snapshot(minute: i64, player_values: &[(u64, u64)]) -> StatsSnapshot<BreakCount> {
+    StatsSnapshot {
+        utc_timestamp: timestamp(minute),
+        player_stats: player_values
+            .iter()
+            .map(|&(n, value)| (player(n), BreakCount(value)))
+            .collect(),
+    }
+}
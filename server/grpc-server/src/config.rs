@@ -0,0 +1,35 @@
+use once_cell::sync::Lazy;
+
+#[derive(serde::Deserialize, Debug)]
+pub struct Sentry {
+    pub environment_name: String,
+    pub dsn: Option<String>,
+}
+
+pub static SENTRY_CONFIG: Lazy<Sentry> =
+    Lazy::new(|| envy::prefixed("SENTRY_").from_env::<Sentry>().unwrap());
+
+#[derive(serde::Deserialize, Debug)]
+pub struct GrpcServer {
+    pub port: u16,
+    /// データベースが利用可能かどうかを調べ、ヘルスチェックの状態を更新する間隔 (秒)。
+    #[serde(default = "default_health_check_interval_seconds")]
+    pub health_check_interval_seconds: u64,
+    /// 新たに記録されたデータ点を購読者に通知するために、データベースを調べる間隔 (秒)。
+    #[serde(default = "default_snapshot_point_poll_interval_seconds")]
+    pub snapshot_point_poll_interval_seconds: u64,
+}
+
+const fn default_health_check_interval_seconds() -> u64 {
+    5
+}
+
+const fn default_snapshot_point_poll_interval_seconds() -> u64 {
+    5
+}
+
+pub static GRPC_SERVER_CONFIG: Lazy<GrpcServer> = Lazy::new(|| {
+    envy::prefixed("GRPC_SERVER_")
+        .from_env::<GrpcServer>()
+        .unwrap()
+});
@@ -0,0 +1,382 @@
+use chrono::{DateTime, Utc};
+use tonic::Status;
+
+use domain::models::{
+    AllStatsSnapshot, NumericStats, Player, PlayerUuidString, RankedPlayer, Ranking,
+    RecordedSnapshotPoint, ResampledStats, SnapshotComparison, SnapshotPointId,
+    SnapshotPointMetadata, SnapshotSummary, StatsGains, StatsSnapshot, TimeSeriesPoints,
+    TimeSeriesSampling, TimestampedRank, TimestampedStats,
+};
+use domain::repositories::TimeBasedSnapshotSearchCondition;
+use usecases::StatsKind;
+
+use crate::buf_generated::gigantic_minecraft::seichi_timed_stats_conifers::v1 as proto;
+
+/// リクエストの内容が不正であることを表すエラー。
+///
+/// `Status` は `Result` のエラーとして持ち回るには大きいため、リクエストの変換ではこちらを返し、
+/// `?` によって `INVALID_ARGUMENT` の `Status` に変換する。
+#[derive(Debug)]
+pub struct InvalidRequest(String);
+
+impl From<InvalidRequest> for Status {
+    fn from(error: InvalidRequest) -> Self {
+        Self::invalid_argument(error.0)
+    }
+}
+
+pub fn stats_kind_from_proto(stats_kind: i32) -> Result<StatsKind, InvalidRequest> {
+    match proto::StatsKind::from_i32(stats_kind) {
+        Some(proto::StatsKind::BreakCount) => Ok(StatsKind::BreakCount),
+        Some(proto::StatsKind::BuildCount) => Ok(StatsKind::BuildCount),
+        Some(proto::StatsKind::PlayTicks) => Ok(StatsKind::PlayTicks),
+        Some(proto::StatsKind::VoteCount) => Ok(StatsKind::VoteCount),
+        Some(proto::StatsKind::Unspecified) | None => {
+            Err(InvalidRequest("stats_kind is not specified".to_owned()))
+        }
+    }
+}
+
+pub fn timestamp_from_proto(
+    timestamp: pbjson_types::Timestamp,
+) -> Result<DateTime<Utc>, InvalidRequest> {
+    DateTime::<Utc>::try_from(timestamp)
+        .map_err(|error| InvalidRequest(format!("invalid timestamp: {error}")))
+}
+
+pub fn required_timestamp_from_proto(
+    timestamp: Option<pbjson_types::Timestamp>,
+    field_name: &str,
+) -> Result<DateTime<Utc>, InvalidRequest> {
+    timestamp_from_proto(
+        timestamp.ok_or_else(|| InvalidRequest(format!("{field_name} is missing")))?,
+    )
+}
+
+pub fn player_from_proto(player_uuid: &String) -> Result<Player, InvalidRequest> {
+    let uuid = PlayerUuidString::from_string(player_uuid)
+        .map_err(|error| InvalidRequest(format!("invalid player_uuid: {error}")))?;
+
+    Ok(Player { uuid })
+}
+
+pub fn sampling_from_proto(
+    from: Option<pbjson_types::Timestamp>,
+    to: Option<pbjson_types::Timestamp>,
+    interval: Option<pbjson_types::Duration>,
+) -> Result<TimeSeriesSampling, InvalidRequest> {
+    let from = required_timestamp_from_proto(from, "from")?;
+    let to = required_timestamp_from_proto(to, "to")?;
+    let interval = interval
+        .ok_or_else(|| InvalidRequest("interval is missing".to_owned()))
+        .and_then(|interval| {
+            std::time::Duration::try_from(interval)
+                .ok()
+                .and_then(|interval| chrono::Duration::from_std(interval).ok())
+                .ok_or_else(|| InvalidRequest("invalid interval".to_owned()))
+        })?;
+
+    TimeSeriesSampling::new(from, to, interval).map_err(|error| InvalidRequest(error.to_string()))
+}
+
+/// `interval` が指定されていれば標本化された時刻を、そうでなければ記録されたすべてのデータ点の時刻を表す。
+pub fn time_series_points_from_proto(
+    from: Option<pbjson_types::Timestamp>,
+    to: Option<pbjson_types::Timestamp>,
+    interval: Option<pbjson_types::Duration>,
+) -> Result<TimeSeriesPoints, InvalidRequest> {
+    match interval {
+        Some(interval) => Ok(TimeSeriesPoints::Sampled(sampling_from_proto(
+            from,
+            to,
+            Some(interval),
+        )?)),
+        None => Ok(TimeSeriesPoints::Recorded {
+            from: required_timestamp_from_proto(from, "from")?,
+            to: required_timestamp_from_proto(to, "to")?,
+        }),
+    }
+}
+
+pub fn condition_from_proto(
+    condition: Option<proto::SnapshotSearchCondition>,
+) -> Result<TimeBasedSnapshotSearchCondition, InvalidRequest> {
+    use proto::snapshot_search_condition::Condition;
+
+    match condition.and_then(|condition| condition.condition) {
+        Some(Condition::NewestBefore(timestamp)) => Ok(
+            TimeBasedSnapshotSearchCondition::NewestBefore(timestamp_from_proto(timestamp)?),
+        ),
+        Some(Condition::OldestAfter(timestamp)) => Ok(
+            TimeBasedSnapshotSearchCondition::OldestAfter(timestamp_from_proto(timestamp)?),
+        ),
+        Some(Condition::Nearest(timestamp)) => Ok(TimeBasedSnapshotSearchCondition::Nearest(
+            timestamp_from_proto(timestamp)?,
+        )),
+        Some(Condition::Exact(timestamp)) => Ok(TimeBasedSnapshotSearchCondition::Exact(
+            timestamp_from_proto(timestamp)?,
+        )),
+        Some(Condition::FullSnapshotPointId(id)) => Ok(TimeBasedSnapshotSearchCondition::AtPoint(
+            SnapshotPointId::Full(id),
+        )),
+        Some(Condition::DiffPointId(id)) => Ok(TimeBasedSnapshotSearchCondition::AtPoint(
+            SnapshotPointId::Diff(id),
+        )),
+        None => Err(InvalidRequest("condition is missing".to_owned())),
+    }
+}
+
+/// すべての種類の統計量をまとめて検索するための条件に変換する。
+///
+/// データ点の ID は統計量の種類ごとに振られるため、すべての種類の統計量に共通する意味を持たない。
+/// そのため、データ点の ID による条件は受け付けない。
+pub fn all_stats_condition_from_proto(
+    condition: Option<proto::SnapshotSearchCondition>,
+) -> Result<TimeBasedSnapshotSearchCondition, InvalidRequest> {
+    match condition_from_proto(condition)? {
+        TimeBasedSnapshotSearchCondition::AtPoint(_) => Err(InvalidRequest(
+            "snapshot point ids cannot be used to search snapshots of all stats kinds".to_owned(),
+        )),
+        condition => Ok(condition),
+    }
+}
+
+pub fn player_stats_to_proto<'a, Stats: NumericStats + 'a>(
+    player_stats: impl IntoIterator<Item = (&'a Player, &'a Stats)>,
+) -> anyhow::Result<Vec<proto::PlayerStatsValue>> {
+    player_stats
+        .into_iter()
+        .map(|(player, stats)| {
+            anyhow::Ok(proto::PlayerStatsValue {
+                player_uuid: player.uuid.as_str()?.to_owned(),
+                value: stats.raw_value(),
+            })
+        })
+        .collect()
+}
+
+pub fn snapshot_to_proto<Stats: NumericStats>(
+    snapshot: &StatsSnapshot<Stats>,
+) -> anyhow::Result<proto::StatsSnapshot> {
+    Ok(proto::StatsSnapshot {
+        timestamp: Some(snapshot.utc_timestamp.into()),
+        player_stats: player_stats_to_proto(&snapshot.player_stats)?,
+    })
+}
+
+fn ranked_players_to_proto<Value>(
+    ranked_players: Vec<RankedPlayer<Value>>,
+    raw_value: impl Fn(&Value) -> u64,
+) -> anyhow::Result<Vec<proto::RankedPlayer>> {
+    ranked_players
+        .into_iter()
+        .map(|ranked_player| {
+            anyhow::Ok(proto::RankedPlayer {
+                rank: ranked_player.rank,
+                player_uuid: ranked_player.player.uuid.as_str()?.to_owned(),
+                value: raw_value(&ranked_player.value),
+            })
+        })
+        .collect()
+}
+
+pub fn ranking_to_proto<Stats: NumericStats>(
+    ranking: Ranking<Stats>,
+) -> anyhow::Result<proto::Ranking> {
+    Ok(proto::Ranking {
+        timestamp: Some(ranking.utc_timestamp.into()),
+        total_player_count: ranking.total_player_count,
+        top_players: ranked_players_to_proto(ranking.top_players, Stats::raw_value)?,
+    })
+}
+
+pub fn snapshot_summary_to_proto(summary: SnapshotSummary) -> proto::SnapshotSummary {
+    proto::SnapshotSummary {
+        timestamp: Some(summary.utc_timestamp.into()),
+        player_count: summary.player_count,
+        sum: summary.sum,
+        mean: summary.mean,
+        median: summary.median,
+        min: summary.min,
+        max: summary.max,
+        percentiles: summary
+            .percentiles
+            .into_iter()
+            .map(|percentile| proto::Percentile {
+                percentage: percentile.percentage,
+                value: percentile.value,
+            })
+            .collect(),
+    }
+}
+
+pub fn stats_gains_to_proto(
+    gains: &StatsGains,
+    limit: Option<usize>,
+) -> anyhow::Result<proto::GetStatsGainsResponse> {
+    let top_players = gains.top_players(limit.unwrap_or(gains.player_gains.len()));
+
+    Ok(proto::GetStatsGainsResponse {
+        from_snapshot_timestamp: gains.from_utc_timestamp.map(Into::into),
+        to_snapshot_timestamp: Some(gains.to_utc_timestamp.into()),
+        total_player_count: gains.player_gains.len() as u64,
+        top_players: ranked_players_to_proto(top_players, |gain| *gain)?,
+    })
+}
+
+pub fn snapshot_comparison_to_proto<Stats: NumericStats>(
+    comparison: &SnapshotComparison<Stats>,
+) -> anyhow::Result<proto::CompareSnapshotsResponse> {
+    let changes = comparison
+        .changes
+        .iter()
+        .map(|change| {
+            anyhow::Ok(proto::PlayerStatsChange {
+                player_uuid: change.player.uuid.as_str()?.to_owned(),
+                old_value: change.old_stats.as_ref().map(NumericStats::raw_value),
+                new_value: change.new_stats.as_ref().map(NumericStats::raw_value),
+            })
+        })
+        .collect::<anyhow::Result<_>>()?;
+
+    Ok(proto::CompareSnapshotsResponse {
+        from_snapshot_timestamp: comparison.old_utc_timestamp.map(Into::into),
+        to_snapshot_timestamp: Some(comparison.new_utc_timestamp.into()),
+        changes,
+    })
+}
+
+pub fn timestamped_stats_to_proto<Stats: NumericStats>(
+    timestamped_stats: &TimestampedStats<Stats>,
+) -> proto::TimestampedValue {
+    proto::TimestampedValue {
+        timestamp: Some(timestamped_stats.utc_timestamp.into()),
+        value: timestamped_stats.stats.raw_value(),
+    }
+}
+
+pub fn snapshot_point_metadata_to_proto(
+    metadata: &SnapshotPointMetadata,
+) -> proto::SnapshotPointMetadata {
+    use proto::snapshot_point_metadata::Point;
+
+    let point = match *metadata {
+        SnapshotPointMetadata::Full { id, .. } => {
+            Point::FullSnapshotPoint(proto::FullSnapshotPointMetadata { id })
+        }
+        SnapshotPointMetadata::Diff {
+            id,
+            root_full_snapshot_point_id,
+            previous_diff_point_id,
+            diff_record_count,
+            ..
+        } => Point::DiffPoint(proto::DiffPointMetadata {
+            id,
+            root_full_snapshot_point_id,
+            previous_diff_point_id,
+            diff_record_count,
+        }),
+    };
+
+    proto::SnapshotPointMetadata {
+        timestamp: Some(metadata.utc_timestamp().into()),
+        point: Some(point),
+    }
+}
+
+pub fn all_stats_snapshot_to_proto(
+    snapshot: AllStatsSnapshot,
+) -> anyhow::Result<proto::GetAllStatsSnapshotResponse> {
+    let player_stats = snapshot
+        .player_stats
+        .into_iter()
+        .map(|(player, stats)| {
+            anyhow::Ok(proto::PlayerAllStatsValue {
+                player_uuid: player.uuid.as_str()?.to_owned(),
+                break_count: stats.break_count.as_ref().map(NumericStats::raw_value),
+                build_count: stats.build_count.as_ref().map(NumericStats::raw_value),
+                play_ticks: stats.play_ticks.as_ref().map(NumericStats::raw_value),
+                vote_count: stats.vote_count.as_ref().map(NumericStats::raw_value),
+            })
+        })
+        .collect::<anyhow::Result<_>>()?;
+
+    Ok(proto::GetAllStatsSnapshotResponse {
+        break_count_timestamp: snapshot.break_count_utc_timestamp.map(Into::into),
+        build_count_timestamp: snapshot.build_count_utc_timestamp.map(Into::into),
+        play_ticks_timestamp: snapshot.play_ticks_utc_timestamp.map(Into::into),
+        vote_count_timestamp: snapshot.vote_count_utc_timestamp.map(Into::into),
+        player_stats,
+    })
+}
+
+pub fn resampled_stats_to_proto<Stats: NumericStats>(
+    resampled_stats: &ResampledStats<Stats>,
+) -> anyhow::Result<proto::ResampledPlayerStats> {
+    Ok(proto::ResampledPlayerStats {
+        sample_timestamp: Some(resampled_stats.sample_utc_timestamp.into()),
+        snapshot_timestamp: resampled_stats.snapshot_utc_timestamp.map(Into::into),
+        player_stats: player_stats_to_proto(&resampled_stats.player_stats)?,
+    })
+}
+
+pub fn timestamped_rank_to_proto(timestamped_rank: &TimestampedRank) -> proto::TimestampedRank {
+    proto::TimestampedRank {
+        timestamp: Some(timestamped_rank.utc_timestamp.into()),
+        snapshot_timestamp: timestamped_rank.snapshot_utc_timestamp.map(Into::into),
+        rank: timestamped_rank.rank,
+        total_ranked_player_count: timestamped_rank.total_ranked_player_count,
+    }
+}
+
+pub fn recorded_snapshot_point_to_proto<Stats: NumericStats>(
+    stats_kind: proto::StatsKind,
+    recorded_point: &RecordedSnapshotPoint<Stats>,
+) -> anyhow::Result<proto::SnapshotPointRecordedEvent> {
+    Ok(proto::SnapshotPointRecordedEvent {
+        stats_kind: stats_kind.into(),
+        player_stats: player_stats_to_proto(&recorded_point.player_stats)?,
+        removed_player_uuids: recorded_point
+            .removed_players
+            .iter()
+            .map(|player| anyhow::Ok(player.uuid.as_str()?.to_owned()))
+            .collect::<anyhow::Result<_>>()?,
+        point: Some(snapshot_point_metadata_to_proto(&recorded_point.metadata)),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proto::snapshot_search_condition::Condition;
+
+    fn condition(condition: Condition) -> proto::SnapshotSearchCondition {
+        proto::SnapshotSearchCondition {
+            condition: Some(condition),
+        }
+    }
+
+    #[test]
+    fn all_stats_condition_rejects_snapshot_point_ids() {
+        for point_id_condition in [Condition::FullSnapshotPointId(1), Condition::DiffPointId(1)] {
+            let status = Status::from(
+                all_stats_condition_from_proto(Some(condition(point_id_condition))).unwrap_err(),
+            );
+
+            assert_eq!(status.code(), tonic::Code::InvalidArgument);
+        }
+    }
+
+    #[test]
+    fn all_stats_condition_accepts_time_based_conditions() {
+        let timestamp = pbjson_types::Timestamp {
+            seconds: 0,
+            nanos: 0,
+        };
+
+        assert!(matches!(
+            all_stats_condition_from_proto(Some(condition(Condition::Nearest(timestamp)))),
+            Ok(TimeBasedSnapshotSearchCondition::Nearest(_))
+        ));
+    }
+}
@@ -0,0 +1,158 @@
+#![deny(clippy::all, clippy::cargo)]
+#![warn(clippy::nursery, clippy::pedantic)]
+#![allow(clippy::cargo_common_metadata)]
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use tonic::codec::CompressionEncoding;
+use tonic::server::NamedService;
+use tonic_health::server::HealthReporter;
+use tonic_health::ServingStatus;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::Layer;
+
+use buf_generated::gigantic_minecraft::seichi_timed_stats_conifers::v1::read_service_server::ReadServiceServer;
+use buf_generated::gigantic_minecraft::seichi_timed_stats_conifers::v1::FILE_DESCRIPTOR_SET;
+use infra_db_repository_impl::{config::Database, DatabaseAvailabilityProbe, DatabaseConnector};
+
+use crate::config::{GRPC_SERVER_CONFIG, SENTRY_CONFIG};
+use crate::service::ReadServiceImpl;
+use crate::subscription::{watch_recorded_snapshot_points, SNAPSHOT_POINT_EVENT_CHANNEL_CAPACITY};
+
+mod config;
+mod conversions;
+mod service;
+mod subscription;
+
+#[allow(dead_code)]
+#[allow(clippy::nursery, clippy::pedantic, clippy::all)]
+mod buf_generated {
+    include!("gen/mod.rs");
+}
+
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        tokio::signal::ctrl_c()
+            .await
+            .expect("failed to install Ctrl+C handler");
+    };
+
+    let terminate = async {
+        tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+            .expect("failed to install SIGTERM handler")
+            .recv()
+            .await;
+    };
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
+
+    tracing::info!("Shutting down...");
+}
+
+/// `interval` ごとにデータベースが利用可能かどうかを調べ、その結果をヘルスチェックの状態に反映し続ける。
+///
+/// Read Service はすべてのリクエストでデータベースを必要とするため、
+/// Read Service とサーバー全体 (サービス名が空文字列のもの) の状態を同じものとして扱う。
+async fn report_database_availability(
+    mut health_reporter: HealthReporter,
+    probe: DatabaseAvailabilityProbe,
+    interval: Duration,
+) {
+    let mut interval = tokio::time::interval(interval);
+
+    loop {
+        interval.tick().await;
+
+        let status = match probe.check().await {
+            Ok(()) => ServingStatus::Serving,
+            Err(error) => {
+                tracing::warn!("Database is unavailable: {error:?}");
+                ServingStatus::NotServing
+            }
+        };
+
+        health_reporter
+            .set_service_status(
+                ReadServiceServer::<ReadServiceImpl<DatabaseConnector>>::NAME,
+                status,
+            )
+            .await;
+        health_reporter.set_service_status("", status).await;
+    }
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // initialize tracing
+    // see https://github.com/tokio-rs/axum/blob/79a0a54bc9f0f585c974b5e6793541baff980662/examples/tracing-aka-logging/src/main.rs
+    tracing_subscriber::registry()
+        .with(sentry::integrations::tracing::layer())
+        .with(
+            tracing_subscriber::fmt::layer().with_filter(tracing_subscriber::EnvFilter::new(
+                std::env::var("RUST_LOG").unwrap_or_else(|_| "info".into()),
+            )),
+        )
+        .init();
+
+    // setup sentry
+    // only send sentry events when we are not running locally
+    let _sentry_client_guard = if SENTRY_CONFIG.environment_name != "local" {
+        Some(sentry::init((
+            SENTRY_CONFIG.dsn.clone(),
+            sentry::ClientOptions {
+                release: sentry::release_name!(),
+                traces_sample_rate: 0.1,
+                environment: Some(SENTRY_CONFIG.environment_name.clone().into()),
+                shutdown_timeout: Duration::from_secs(10),
+                ..Default::default()
+            },
+        )))
+    } else {
+        None
+    };
+
+    let repository = Arc::new(DatabaseConnector::try_new(Database::from_env()?).await?);
+
+    let (health_reporter, health_service) = tonic_health::server::health_reporter();
+    tokio::spawn(report_database_availability(
+        health_reporter,
+        repository.availability_probe(),
+        Duration::from_secs(GRPC_SERVER_CONFIG.health_check_interval_seconds),
+    ));
+
+    let reflection_service = tonic_reflection::server::Builder::configure()
+        .register_encoded_file_descriptor_set(FILE_DESCRIPTOR_SET)
+        .register_encoded_file_descriptor_set(tonic_health::pb::FILE_DESCRIPTOR_SET)
+        .build()?;
+
+    let (snapshot_point_events, _) =
+        tokio::sync::broadcast::channel(SNAPSHOT_POINT_EVENT_CHANNEL_CAPACITY);
+    tokio::spawn(watch_recorded_snapshot_points(
+        Arc::clone(&repository),
+        snapshot_point_events.clone(),
+        Duration::from_secs(GRPC_SERVER_CONFIG.snapshot_point_poll_interval_seconds),
+    ));
+
+    let read_service =
+        ReadServiceServer::new(ReadServiceImpl::new(repository, snapshot_point_events))
+            .accept_compressed(CompressionEncoding::Gzip)
+            .send_compressed(CompressionEncoding::Gzip);
+
+    let address = SocketAddr::from(([0, 0, 0, 0], GRPC_SERVER_CONFIG.port));
+    tracing::info!("Listening on {address}");
+
+    tonic::transport::Server::builder()
+        .add_service(health_service)
+        .add_service(reflection_service)
+        .add_service(read_service)
+        .serve_with_shutdown(address, shutdown_signal())
+        .await?;
+
+    Ok(())
+}
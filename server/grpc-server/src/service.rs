@@ -0,0 +1,582 @@
+use std::collections::HashSet;
+use std::fmt::Display;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use futures_util::{Stream, TryStreamExt};
+use tokio::sync::broadcast::error::RecvError;
+use tonic::{Request, Response, Status};
+
+use domain::errors::InvalidArgumentError;
+use domain::models::NumericStats;
+use domain::repositories::PlayerTimedStatsRepository;
+use usecases::{
+    with_stats_type, CompareSnapshots, GetAllStatsSnapshot, GetPlayerRankHistory, GetPlayerStats,
+    GetPlayerStatsHistory, GetRanking, GetResampledPlayerStats, GetSnapshot, GetSnapshotInChunks,
+    GetSnapshotOfPlayers, GetSnapshotSummary, GetStatsGains, ListSnapshotPoints,
+    TimedStatsRepository,
+};
+
+use crate::buf_generated::gigantic_minecraft::seichi_timed_stats_conifers::v1 as proto;
+use crate::conversions::{
+    all_stats_condition_from_proto, all_stats_snapshot_to_proto, condition_from_proto,
+    player_from_proto, player_stats_to_proto, ranking_to_proto, required_timestamp_from_proto,
+    resampled_stats_to_proto, sampling_from_proto, snapshot_comparison_to_proto,
+    snapshot_point_metadata_to_proto, snapshot_summary_to_proto, snapshot_to_proto,
+    stats_gains_to_proto, stats_kind_from_proto, time_series_points_from_proto,
+    timestamped_rank_to_proto, timestamped_stats_to_proto,
+};
+use crate::subscription::SnapshotPointEventSender;
+
+const DEFAULT_SNAPSHOT_CHUNK_SIZE: usize = 5000;
+const MAX_SNAPSHOT_CHUNK_SIZE: usize = 50000;
+
+type StreamSnapshotResponseStream =
+    Pin<Box<dyn Stream<Item = Result<proto::StreamSnapshotResponse, Status>> + Send>>;
+
+type SnapshotPointRecordedEventStream =
+    Pin<Box<dyn Stream<Item = Result<proto::SnapshotPointRecordedEvent, Status>> + Send>>;
+
+/// エラーの詳細はログにのみ残し、クライアントにはデータベースのエラーなどの内部の情報を返さない。
+fn internal_error(error: impl Display) -> Status {
+    tracing::error!("{error:#}");
+    Status::internal("internal error")
+}
+
+/// ユースケースのエラーを `Status` に変換する。
+/// 引数が不正であることによるエラーは `INVALID_ARGUMENT` とし、それ以外は内部のエラーとして扱う。
+fn usecase_error(error: anyhow::Error) -> Status {
+    match error.downcast::<InvalidArgumentError>() {
+        Ok(error) => Status::invalid_argument(error.to_string()),
+        Err(error) => internal_error(error),
+    }
+}
+
+pub struct ReadServiceImpl<Repository> {
+    repository: Arc<Repository>,
+    snapshot_point_events: SnapshotPointEventSender,
+}
+
+impl<Repository: TimedStatsRepository> ReadServiceImpl<Repository> {
+    pub const fn new(
+        repository: Arc<Repository>,
+        snapshot_point_events: SnapshotPointEventSender,
+    ) -> Self {
+        Self {
+            repository,
+            snapshot_point_events,
+        }
+    }
+
+    async fn get_snapshot_of<Stats>(
+        &self,
+        request: proto::GetSnapshotRequest,
+    ) -> Result<proto::GetSnapshotResponse, Status>
+    where
+        Stats: NumericStats + Send + 'static,
+        Repository: PlayerTimedStatsRepository<Stats>,
+    {
+        let condition = condition_from_proto(request.condition)?;
+        let players = request
+            .players
+            .map(|players| {
+                players
+                    .player_uuids
+                    .iter()
+                    .map(player_from_proto)
+                    .collect::<Result<HashSet<_>, _>>()
+            })
+            .transpose()?;
+
+        let snapshot = match players {
+            Some(players) => {
+                GetSnapshotOfPlayers::new(self.repository.as_ref())
+                    .execute::<Stats>(players, condition)
+                    .await
+            }
+            None => {
+                GetSnapshot::new(self.repository.as_ref())
+                    .execute::<Stats>(condition)
+                    .await
+            }
+        }
+        .map_err(internal_error)?
+        .ok_or_else(|| Status::not_found("no snapshot matches the condition"))?;
+
+        Ok(proto::GetSnapshotResponse {
+            snapshot: Some(snapshot_to_proto(&snapshot).map_err(internal_error)?),
+        })
+    }
+
+    async fn stream_snapshot_of<Stats>(
+        &self,
+        request: proto::StreamSnapshotRequest,
+    ) -> Result<StreamSnapshotResponseStream, Status>
+    where
+        Stats: NumericStats + Send + 'static,
+        Repository: PlayerTimedStatsRepository<Stats>,
+    {
+        let condition = condition_from_proto(request.condition)?;
+        let chunk_size = request
+            .chunk_size
+            .map_or(DEFAULT_SNAPSHOT_CHUNK_SIZE, |chunk_size| {
+                (chunk_size as usize).min(MAX_SNAPSHOT_CHUNK_SIZE)
+            });
+
+        let snapshot_chunks = GetSnapshotInChunks::new(self.repository.as_ref())
+            .execute::<Stats>(condition, chunk_size)
+            .await
+            .map_err(usecase_error)?
+            .ok_or_else(|| Status::not_found("no snapshot matches the condition"))?;
+
+        let timestamp = pbjson_types::Timestamp::from(snapshot_chunks.utc_timestamp);
+        let total_player_count = snapshot_chunks.player_stats_count as u64;
+
+        // チャンクは、クライアントへの送信が進むにつれて一つずつ proto に変換される
+        let responses = snapshot_chunks.chunks.map(move |chunk| {
+            player_stats_to_proto(chunk.iter().map(|(player, stats)| (player, stats))).map(
+                |player_stats| proto::StreamSnapshotResponse {
+                    timestamp: Some(timestamp.clone()),
+                    total_player_count,
+                    player_stats,
+                },
+            )
+        });
+
+        Ok(Box::pin(
+            futures_util::stream::iter(responses).map_err(internal_error),
+        ))
+    }
+
+    async fn get_player_stats_of<Stats>(
+        &self,
+        request: proto::GetPlayerStatsRequest,
+    ) -> Result<proto::GetPlayerStatsResponse, Status>
+    where
+        Stats: NumericStats + Send + 'static,
+        Repository: PlayerTimedStatsRepository<Stats>,
+    {
+        let player = player_from_proto(&request.player_uuid)?;
+        let condition = condition_from_proto(request.condition)?;
+
+        let stats = GetPlayerStats::new(self.repository.as_ref())
+            .execute::<Stats>(player, condition)
+            .await
+            .map_err(internal_error)?
+            .ok_or_else(|| Status::not_found("no stats of the player matches the condition"))?;
+
+        Ok(proto::GetPlayerStatsResponse {
+            stats: Some(timestamped_stats_to_proto(&stats)),
+        })
+    }
+
+    async fn get_player_stats_history_of<Stats>(
+        &self,
+        request: proto::GetPlayerStatsHistoryRequest,
+    ) -> Result<proto::GetPlayerStatsHistoryResponse, Status>
+    where
+        Stats: NumericStats + Send + 'static,
+        Repository: PlayerTimedStatsRepository<Stats>,
+    {
+        let player = player_from_proto(&request.player_uuid)?;
+        let from = required_timestamp_from_proto(request.from, "from")?;
+        let to = required_timestamp_from_proto(request.to, "to")?;
+
+        let history = GetPlayerStatsHistory::new(self.repository.as_ref())
+            .execute::<Stats>(player, from, to)
+            .await
+            .map_err(internal_error)?;
+
+        Ok(proto::GetPlayerStatsHistoryResponse {
+            history: history.iter().map(timestamped_stats_to_proto).collect(),
+        })
+    }
+
+    async fn get_resampled_player_stats_of<Stats>(
+        &self,
+        request: proto::GetResampledPlayerStatsRequest,
+    ) -> Result<proto::GetResampledPlayerStatsResponse, Status>
+    where
+        Stats: NumericStats + Send + 'static,
+        Repository: PlayerTimedStatsRepository<Stats>,
+    {
+        let players = request
+            .player_uuids
+            .iter()
+            .map(player_from_proto)
+            .collect::<Result<HashSet<_>, _>>()?;
+        let sampling = sampling_from_proto(request.from, request.to, request.interval)?;
+
+        let samples = GetResampledPlayerStats::new(self.repository.as_ref())
+            .execute::<Stats>(players, sampling)
+            .await
+            .map_err(internal_error)?;
+
+        Ok(proto::GetResampledPlayerStatsResponse {
+            samples: samples
+                .iter()
+                .map(resampled_stats_to_proto)
+                .collect::<anyhow::Result<_>>()
+                .map_err(internal_error)?,
+        })
+    }
+
+    async fn get_player_rank_history_of<Stats>(
+        &self,
+        request: proto::GetPlayerRankHistoryRequest,
+    ) -> Result<proto::GetPlayerRankHistoryResponse, Status>
+    where
+        Stats: Send + 'static,
+        Repository: PlayerTimedStatsRepository<Stats>,
+    {
+        let player = player_from_proto(&request.player_uuid)?;
+        let points = time_series_points_from_proto(request.from, request.to, request.interval)?;
+
+        let history = GetPlayerRankHistory::new(self.repository.as_ref())
+            .execute::<Stats>(player, points)
+            .await
+            .map_err(internal_error)?;
+
+        Ok(proto::GetPlayerRankHistoryResponse {
+            history: history.iter().map(timestamped_rank_to_proto).collect(),
+        })
+    }
+
+    async fn list_snapshot_points_of<Stats>(
+        &self,
+        request: proto::ListSnapshotPointsRequest,
+    ) -> Result<proto::ListSnapshotPointsResponse, Status>
+    where
+        Stats: Send + 'static,
+        Repository: PlayerTimedStatsRepository<Stats>,
+    {
+        let from = required_timestamp_from_proto(request.from, "from")?;
+        let to = required_timestamp_from_proto(request.to, "to")?;
+
+        let snapshot_points = ListSnapshotPoints::new(self.repository.as_ref())
+            .execute::<Stats>(from, to)
+            .await
+            .map_err(internal_error)?;
+
+        Ok(proto::ListSnapshotPointsResponse {
+            snapshot_points: snapshot_points
+                .iter()
+                .map(snapshot_point_metadata_to_proto)
+                .collect(),
+        })
+    }
+
+    async fn get_ranking_of<Stats>(
+        &self,
+        request: proto::GetRankingRequest,
+    ) -> Result<proto::GetRankingResponse, Status>
+    where
+        Stats: NumericStats + Ord + Clone + Send + 'static,
+        Repository: PlayerTimedStatsRepository<Stats>,
+    {
+        if request.limit == 0 {
+            return Err(Status::invalid_argument("limit must be positive"));
+        }
+        let condition = condition_from_proto(request.condition)?;
+
+        let ranking = GetRanking::new(self.repository.as_ref())
+            .execute::<Stats>(condition, request.limit as usize)
+            .await
+            .map_err(internal_error)?
+            .ok_or_else(|| Status::not_found("no snapshot matches the condition"))?;
+
+        Ok(proto::GetRankingResponse {
+            ranking: Some(ranking_to_proto(ranking).map_err(internal_error)?),
+        })
+    }
+
+    async fn get_snapshot_summary_of<Stats>(
+        &self,
+        request: proto::GetSnapshotSummaryRequest,
+    ) -> Result<proto::GetSnapshotSummaryResponse, Status>
+    where
+        Stats: NumericStats + Send + 'static,
+        Repository: PlayerTimedStatsRepository<Stats>,
+    {
+        let condition = condition_from_proto(request.condition)?;
+
+        let summary = GetSnapshotSummary::new(self.repository.as_ref())
+            .execute::<Stats>(condition, &request.percentages)
+            .await
+            .map_err(usecase_error)?
+            .ok_or_else(|| Status::not_found("no snapshot matches the condition"))?;
+
+        Ok(proto::GetSnapshotSummaryResponse {
+            summary: Some(snapshot_summary_to_proto(summary)),
+        })
+    }
+
+    async fn get_stats_gains_of<Stats>(
+        &self,
+        request: proto::GetStatsGainsRequest,
+    ) -> Result<proto::GetStatsGainsResponse, Status>
+    where
+        Stats: NumericStats + Send + 'static,
+        Repository: PlayerTimedStatsRepository<Stats>,
+    {
+        let from = required_timestamp_from_proto(request.from, "from")?;
+        let to = required_timestamp_from_proto(request.to, "to")?;
+
+        let gains = GetStatsGains::new(self.repository.as_ref())
+            .execute::<Stats>(from, to)
+            .await
+            .map_err(usecase_error)?
+            .ok_or_else(|| Status::not_found("no snapshot is recorded before `to`"))?;
+
+        stats_gains_to_proto(&gains, request.limit.map(|limit| limit as usize))
+            .map_err(internal_error)
+    }
+
+    async fn compare_snapshots_of<Stats>(
+        &self,
+        request: proto::CompareSnapshotsRequest,
+    ) -> Result<proto::CompareSnapshotsResponse, Status>
+    where
+        Stats: NumericStats + Clone + Eq + Send + 'static,
+        Repository: PlayerTimedStatsRepository<Stats>,
+    {
+        let from = required_timestamp_from_proto(request.from, "from")?;
+        let to = required_timestamp_from_proto(request.to, "to")?;
+
+        let comparison = CompareSnapshots::new(self.repository.as_ref())
+            .execute::<Stats>(from, to)
+            .await
+            .map_err(usecase_error)?
+            .ok_or_else(|| Status::not_found("no snapshot is recorded before `to`"))?;
+
+        snapshot_comparison_to_proto(&comparison).map_err(internal_error)
+    }
+}
+
+#[tonic::async_trait]
+impl<Repository: TimedStatsRepository> proto::read_service_server::ReadService
+    for ReadServiceImpl<Repository>
+{
+    type StreamSnapshotStream = StreamSnapshotResponseStream;
+    type SubscribeSnapshotPointsStream = SnapshotPointRecordedEventStream;
+
+    #[tracing::instrument(skip(self))]
+    async fn get_snapshot(
+        &self,
+        request: Request<proto::GetSnapshotRequest>,
+    ) -> Result<Response<proto::GetSnapshotResponse>, Status> {
+        let request = request.into_inner();
+
+        let response = with_stats_type!(stats_kind_from_proto(request.stats_kind)?, Stats => {
+            self.get_snapshot_of::<Stats>(request).await
+        })?;
+
+        Ok(Response::new(response))
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn stream_snapshot(
+        &self,
+        request: Request<proto::StreamSnapshotRequest>,
+    ) -> Result<Response<Self::StreamSnapshotStream>, Status> {
+        let request = request.into_inner();
+
+        let stream = with_stats_type!(stats_kind_from_proto(request.stats_kind)?, Stats => {
+            self.stream_snapshot_of::<Stats>(request).await
+        })?;
+
+        Ok(Response::new(stream))
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn get_player_stats(
+        &self,
+        request: Request<proto::GetPlayerStatsRequest>,
+    ) -> Result<Response<proto::GetPlayerStatsResponse>, Status> {
+        let request = request.into_inner();
+
+        let response = with_stats_type!(stats_kind_from_proto(request.stats_kind)?, Stats => {
+            self.get_player_stats_of::<Stats>(request).await
+        })?;
+
+        Ok(Response::new(response))
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn get_player_stats_history(
+        &self,
+        request: Request<proto::GetPlayerStatsHistoryRequest>,
+    ) -> Result<Response<proto::GetPlayerStatsHistoryResponse>, Status> {
+        let request = request.into_inner();
+
+        let response = with_stats_type!(stats_kind_from_proto(request.stats_kind)?, Stats => {
+            self.get_player_stats_history_of::<Stats>(request).await
+        })?;
+
+        Ok(Response::new(response))
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn get_resampled_player_stats(
+        &self,
+        request: Request<proto::GetResampledPlayerStatsRequest>,
+    ) -> Result<Response<proto::GetResampledPlayerStatsResponse>, Status> {
+        let request = request.into_inner();
+
+        let response = with_stats_type!(stats_kind_from_proto(request.stats_kind)?, Stats => {
+            self.get_resampled_player_stats_of::<Stats>(request).await
+        })?;
+
+        Ok(Response::new(response))
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn get_player_rank_history(
+        &self,
+        request: Request<proto::GetPlayerRankHistoryRequest>,
+    ) -> Result<Response<proto::GetPlayerRankHistoryResponse>, Status> {
+        let request = request.into_inner();
+
+        let response = with_stats_type!(stats_kind_from_proto(request.stats_kind)?, Stats => {
+            self.get_player_rank_history_of::<Stats>(request).await
+        })?;
+
+        Ok(Response::new(response))
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn list_snapshot_points(
+        &self,
+        request: Request<proto::ListSnapshotPointsRequest>,
+    ) -> Result<Response<proto::ListSnapshotPointsResponse>, Status> {
+        let request = request.into_inner();
+
+        let response = with_stats_type!(stats_kind_from_proto(request.stats_kind)?, Stats => {
+            self.list_snapshot_points_of::<Stats>(request).await
+        })?;
+
+        Ok(Response::new(response))
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn get_ranking(
+        &self,
+        request: Request<proto::GetRankingRequest>,
+    ) -> Result<Response<proto::GetRankingResponse>, Status> {
+        let request = request.into_inner();
+
+        let response = with_stats_type!(stats_kind_from_proto(request.stats_kind)?, Stats => {
+            self.get_ranking_of::<Stats>(request).await
+        })?;
+
+        Ok(Response::new(response))
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn get_snapshot_summary(
+        &self,
+        request: Request<proto::GetSnapshotSummaryRequest>,
+    ) -> Result<Response<proto::GetSnapshotSummaryResponse>, Status> {
+        let request = request.into_inner();
+
+        let response = with_stats_type!(stats_kind_from_proto(request.stats_kind)?, Stats => {
+            self.get_snapshot_summary_of::<Stats>(request).await
+        })?;
+
+        Ok(Response::new(response))
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn get_stats_gains(
+        &self,
+        request: Request<proto::GetStatsGainsRequest>,
+    ) -> Result<Response<proto::GetStatsGainsResponse>, Status> {
+        let request = request.into_inner();
+
+        let response = with_stats_type!(stats_kind_from_proto(request.stats_kind)?, Stats => {
+            self.get_stats_gains_of::<Stats>(request).await
+        })?;
+
+        Ok(Response::new(response))
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn compare_snapshots(
+        &self,
+        request: Request<proto::CompareSnapshotsRequest>,
+    ) -> Result<Response<proto::CompareSnapshotsResponse>, Status> {
+        let request = request.into_inner();
+
+        let response = with_stats_type!(stats_kind_from_proto(request.stats_kind)?, Stats => {
+            self.compare_snapshots_of::<Stats>(request).await
+        })?;
+
+        Ok(Response::new(response))
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn get_all_stats_snapshot(
+        &self,
+        request: Request<proto::GetAllStatsSnapshotRequest>,
+    ) -> Result<Response<proto::GetAllStatsSnapshotResponse>, Status> {
+        let condition = all_stats_condition_from_proto(request.into_inner().condition)?;
+
+        let snapshot = GetAllStatsSnapshot::new(self.repository.as_ref())
+            .execute(condition)
+            .await
+            .map_err(internal_error)?;
+
+        Ok(Response::new(
+            all_stats_snapshot_to_proto(snapshot).map_err(internal_error)?,
+        ))
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn subscribe_snapshot_points(
+        &self,
+        request: Request<proto::SubscribeSnapshotPointsRequest>,
+    ) -> Result<Response<Self::SubscribeSnapshotPointsStream>, Status> {
+        let stats_kinds = request.into_inner().stats_kinds;
+        if stats_kinds.iter().any(|stats_kind| {
+            matches!(
+                proto::StatsKind::from_i32(*stats_kind),
+                Some(proto::StatsKind::Unspecified) | None
+            )
+        }) {
+            return Err(Status::invalid_argument(
+                "stats_kinds contains an invalid value",
+            ));
+        }
+        let stats_kinds = stats_kinds.into_iter().collect::<HashSet<_>>();
+
+        let receiver = self.snapshot_point_events.subscribe();
+        let events =
+            futures_util::stream::unfold(Some((receiver, stats_kinds)), |state| async move {
+                let (mut receiver, stats_kinds) = state?;
+
+                loop {
+                    match receiver.recv().await {
+                        Ok(event)
+                            if stats_kinds.is_empty()
+                                || stats_kinds.contains(&event.stats_kind) =>
+                        {
+                            return Some((
+                                Ok(event.as_ref().clone()),
+                                Some((receiver, stats_kinds)),
+                            ));
+                        }
+                        Ok(_) => {}
+                        Err(RecvError::Lagged(skipped_event_count)) => {
+                            let status = Status::resource_exhausted(format!(
+                                "subscriber fell behind and missed {skipped_event_count} events"
+                            ));
+                            return Some((Err(status), None));
+                        }
+                        Err(RecvError::Closed) => return None,
+                    }
+                }
+            });
+
+        Ok(Response::new(Box::pin(events)))
+    }
+}
@@ -0,0 +1,110 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::sync::broadcast;
+
+use domain::models::{BreakCount, BuildCount, NumericStats, PlayTicks, VoteCount};
+use domain::repositories::PlayerTimedStatsRepository;
+use usecases::{PollRecordedSnapshotPoints, RecordedSnapshotPointCursor, TimedStatsRepository};
+
+use crate::buf_generated::gigantic_minecraft::seichi_timed_stats_conifers::v1 as proto;
+use crate::conversions::recorded_snapshot_point_to_proto;
+
+/// 新たに記録されたデータ点を購読者に配信するためのチャンネルの送信側。
+pub type SnapshotPointEventSender = broadcast::Sender<Arc<proto::SnapshotPointRecordedEvent>>;
+
+/// 購読者が受信していないイベントを保持しておく最大の個数。これを超えると、古いイベントから失われる。
+pub const SNAPSHOT_POINT_EVENT_CHANNEL_CAPACITY: usize = 256;
+
+/// ある種類の統計量について、新たに記録されたデータ点を取得して `sender` に送信する。
+///
+/// 購読者が一人もいない場合はデータ点を読み出さず、 `cursor` を破棄する。
+/// 購読者が現れた後の最初の呼び出しでは、それまでに記録されたデータ点を送信せずに `cursor` を作るのみとする。
+async fn poll_and_broadcast<Stats, Repository>(
+    repository: &Repository,
+    stats_kind: proto::StatsKind,
+    cursor: &mut Option<RecordedSnapshotPointCursor>,
+    sender: &SnapshotPointEventSender,
+) -> anyhow::Result<()>
+where
+    Stats: NumericStats + Send + 'static,
+    Repository: PlayerTimedStatsRepository<Stats> + Sync,
+{
+    if sender.receiver_count() == 0 {
+        *cursor = None;
+        return Ok(());
+    }
+
+    let usecase = PollRecordedSnapshotPoints::new(repository);
+    let Some(current_cursor) = cursor.as_mut() else {
+        *cursor = Some(usecase.start::<Stats>().await?);
+        return Ok(());
+    };
+
+    let recorded_points = usecase.execute::<Stats>(current_cursor).await?;
+
+    for recorded_point in recorded_points {
+        let event = recorded_snapshot_point_to_proto(stats_kind, &recorded_point)?;
+        // 送信の直前に購読者がいなくなった場合にのみ失敗するため、無視してよい
+        let _ = sender.send(Arc::new(event));
+    }
+
+    Ok(())
+}
+
+/// `interval` ごとにすべての種類の統計量について新たに記録されたデータ点を調べ、 `sender` に送信し続ける。
+///
+/// データ点はインジェスタなどの別プロセスによって記録されるため、
+/// 記録されたことを直接知ることはできず、データベースを定期的に調べることで検出する。
+/// データ点は ID によって検出するため、過去の時刻のデータ点が後から記録された場合も送信される。
+pub async fn watch_recorded_snapshot_points<Repository: TimedStatsRepository>(
+    repository: Arc<Repository>,
+    sender: SnapshotPointEventSender,
+    interval: Duration,
+) {
+    let mut break_count_cursor = None;
+    let mut build_count_cursor = None;
+    let mut play_ticks_cursor = None;
+    let mut vote_count_cursor = None;
+
+    let mut interval = tokio::time::interval(interval);
+
+    loop {
+        interval.tick().await;
+
+        let results = [
+            poll_and_broadcast::<BreakCount, _>(
+                repository.as_ref(),
+                proto::StatsKind::BreakCount,
+                &mut break_count_cursor,
+                &sender,
+            )
+            .await,
+            poll_and_broadcast::<BuildCount, _>(
+                repository.as_ref(),
+                proto::StatsKind::BuildCount,
+                &mut build_count_cursor,
+                &sender,
+            )
+            .await,
+            poll_and_broadcast::<PlayTicks, _>(
+                repository.as_ref(),
+                proto::StatsKind::PlayTicks,
+                &mut play_ticks_cursor,
+                &sender,
+            )
+            .await,
+            poll_and_broadcast::<VoteCount, _>(
+                repository.as_ref(),
+                proto::StatsKind::VoteCount,
+                &mut vote_count_cursor,
+                &sender,
+            )
+            .await,
+        ];
+
+        for error in results.into_iter().filter_map(Result::err) {
+            tracing::error!("Failed to poll recorded snapshot points: {error:?}");
+        }
+    }
+}
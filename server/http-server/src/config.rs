@@ -0,0 +1,21 @@
+use once_cell::sync::Lazy;
+
+#[derive(serde::Deserialize, Debug)]
+pub struct Sentry {
+    pub environment_name: String,
+    pub dsn: Option<String>,
+}
+
+pub static SENTRY_CONFIG: Lazy<Sentry> =
+    Lazy::new(|| envy::prefixed("SENTRY_").from_env::<Sentry>().unwrap());
+
+#[derive(serde::Deserialize, Debug)]
+pub struct HttpServer {
+    pub port: u16,
+}
+
+pub static HTTP_SERVER_CONFIG: Lazy<HttpServer> = Lazy::new(|| {
+    envy::prefixed("HTTP_SERVER_")
+        .from_env::<HttpServer>()
+        .unwrap()
+});
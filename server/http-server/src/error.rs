@@ -0,0 +1,47 @@
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+
+use domain::errors::InvalidArgumentError;
+
+/// API のエラー。クライアントには `{"error": "<メッセージ>"}` の形の JSON として返される。
+#[derive(Debug)]
+pub enum ApiError {
+    BadRequest(String),
+    NotFound(String),
+    Internal(anyhow::Error),
+}
+
+#[derive(serde::Serialize)]
+struct ErrorBody {
+    error: String,
+}
+
+/// 引数が不正であることによるエラーは 400 Bad Request とし、それ以外は内部のエラーとして扱う。
+impl From<anyhow::Error> for ApiError {
+    fn from(error: anyhow::Error) -> Self {
+        match error.downcast::<InvalidArgumentError>() {
+            Ok(error) => Self::BadRequest(error.to_string()),
+            Err(error) => Self::Internal(error),
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let (status, message) = match self {
+            Self::BadRequest(message) => (StatusCode::BAD_REQUEST, message),
+            Self::NotFound(message) => (StatusCode::NOT_FOUND, message),
+            Self::Internal(error) => {
+                // データベースのエラーなどの内部の情報はクライアントに返さず、ログにのみ残す
+                tracing::error!("{error:?}");
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "internal error".to_owned(),
+                )
+            }
+        };
+
+        (status, Json(ErrorBody { error: message })).into_response()
+    }
+}
@@ -0,0 +1,464 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use axum::extract::{Path, Query, State};
+use axum::routing::get;
+use axum::{Json, Router};
+use chrono::{DateTime, Duration, Utc};
+
+use domain::models::{
+    NumericStats, Player, PlayerUuidString, TimeSeriesPoints, TimeSeriesSampling,
+};
+use domain::repositories::{PlayerTimedStatsRepository, TimeBasedSnapshotSearchCondition};
+
+use usecases::{
+    with_stats_type, CompareSnapshots, GetPlayerRankHistory, GetPlayerStatsHistory, GetRanking,
+    GetResampledPlayerStats, GetSnapshot, GetSnapshotOfPlayers, GetSnapshotSummary,
+    TimedStatsRepository,
+};
+
+use crate::error::ApiError;
+use crate::responses::{
+    player_rank_history_to_response, player_stats_history_to_response, ranking_to_response,
+    resampled_player_stats_to_response, snapshot_comparison_to_response,
+    snapshot_summary_to_response, snapshot_to_response, PlayerRankHistoryResponse,
+    PlayerStatsHistoryResponse, RankingResponse, ResampledPlayerStatsResponse,
+    SnapshotComparisonResponse, SnapshotResponse, SnapshotSummaryResponse,
+};
+
+/// パスで指定される統計の種類。
+#[derive(serde::Deserialize, Debug, Clone, Copy)]
+#[serde(rename_all = "kebab-case")]
+pub enum StatsKind {
+    BreakCount,
+    BuildCount,
+    PlayTicks,
+    VoteCount,
+}
+
+impl From<StatsKind> for usecases::StatsKind {
+    fn from(stats_kind: StatsKind) -> Self {
+        match stats_kind {
+            StatsKind::BreakCount => Self::BreakCount,
+            StatsKind::BuildCount => Self::BuildCount,
+            StatsKind::PlayTicks => Self::PlayTicks,
+            StatsKind::VoteCount => Self::VoteCount,
+        }
+    }
+}
+
+const DEFAULT_RANKING_LIMIT: usize = 100;
+
+fn parse_timestamp(timestamp: &str, parameter_name: &str) -> Result<DateTime<Utc>, ApiError> {
+    DateTime::parse_from_rfc3339(timestamp)
+        .map(|timestamp| timestamp.with_timezone(&Utc))
+        .map_err(|error| {
+            ApiError::BadRequest(format!(
+                "`{parameter_name}` is not a valid RFC 3339 timestamp: {error}"
+            ))
+        })
+}
+
+fn player_from_path(player_uuid: &String) -> Result<Player, ApiError> {
+    let uuid = PlayerUuidString::from_string(player_uuid)
+        .map_err(|error| ApiError::BadRequest(format!("invalid player uuid: {error}")))?;
+
+    Ok(Player { uuid })
+}
+
+/// カンマ区切りのプレーヤーの UUID を、プレーヤーの集合に変換する。
+fn players_from_query(player_uuids: &str) -> Result<HashSet<Player>, ApiError> {
+    player_uuids
+        .split(',')
+        .filter(|player_uuid| !player_uuid.is_empty())
+        .map(|player_uuid| player_from_path(&player_uuid.to_owned()))
+        .collect()
+}
+
+/// スナップショットの検索条件を表すクエリパラメータ。いずれか一つのみが指定されなければならない。
+#[derive(serde::Deserialize, Debug)]
+pub struct SnapshotConditionQuery {
+    newest_before: Option<String>,
+    oldest_after: Option<String>,
+    nearest: Option<String>,
+    exact: Option<String>,
+}
+
+impl SnapshotConditionQuery {
+    fn into_condition(self) -> Result<TimeBasedSnapshotSearchCondition, ApiError> {
+        match (self.newest_before, self.oldest_after, self.nearest, self.exact) {
+            (Some(timestamp), None, None, None) => Ok(
+                TimeBasedSnapshotSearchCondition::NewestBefore(parse_timestamp(
+                    &timestamp,
+                    "newest_before",
+                )?),
+            ),
+            (None, Some(timestamp), None, None) => Ok(
+                TimeBasedSnapshotSearchCondition::OldestAfter(parse_timestamp(
+                    &timestamp,
+                    "oldest_after",
+                )?),
+            ),
+            (None, None, Some(timestamp), None) => Ok(TimeBasedSnapshotSearchCondition::Nearest(
+                parse_timestamp(&timestamp, "nearest")?,
+            )),
+            (None, None, None, Some(timestamp)) => Ok(TimeBasedSnapshotSearchCondition::Exact(
+                parse_timestamp(&timestamp, "exact")?,
+            )),
+            _ => Err(ApiError::BadRequest(
+                "exactly one of `newest_before`, `oldest_after`, `nearest` or `exact` must be specified"
+                    .to_owned(),
+            )),
+        }
+    }
+}
+
+/// スナップショットに含めるプレーヤーを制限するためのクエリパラメータ。
+#[derive(serde::Deserialize, Debug)]
+pub struct SnapshotPlayersQuery {
+    /// カンマ区切りのプレーヤーの UUID。指定されなかった場合は、すべてのプレーヤーを含める。
+    players: Option<String>,
+}
+
+#[derive(serde::Deserialize, Debug)]
+pub struct TimeRangeQuery {
+    from: String,
+    to: String,
+}
+
+/// 標本化された時系列を取得するためのクエリパラメータ。
+#[derive(serde::Deserialize, Debug)]
+pub struct ResampledPlayerStatsQuery {
+    /// カンマ区切りのプレーヤーの UUID。
+    players: String,
+    from: String,
+    to: String,
+    /// 標本の間隔 (秒)。
+    interval_seconds: u32,
+}
+
+/// 順位の推移を取得するためのクエリパラメータ。
+#[derive(serde::Deserialize, Debug)]
+pub struct PlayerRankHistoryQuery {
+    from: String,
+    to: String,
+    /// 標本の間隔 (秒)。指定されなかった場合は、期間内に記録されたすべてのデータ点での順位を返す。
+    interval_seconds: Option<u32>,
+}
+
+/// 要約統計量を取得するためのクエリパラメータ。
+#[derive(serde::Deserialize, Debug)]
+pub struct SnapshotSummaryQuery {
+    /// カンマ区切りの、求める百分位数の百分率 (0 以上 100 以下)。
+    percentiles: Option<String>,
+}
+
+impl SnapshotSummaryQuery {
+    fn percentages(&self) -> Result<Vec<f64>, ApiError> {
+        self.percentiles
+            .iter()
+            .flat_map(|percentages| percentages.split(','))
+            .filter(|percentage| !percentage.is_empty())
+            .map(|percentage| {
+                percentage
+                    .parse::<f64>()
+                    .map_err(|_| ApiError::BadRequest(format!("`{percentage}` is not a number")))
+            })
+            .collect()
+    }
+}
+
+#[derive(serde::Deserialize, Debug)]
+pub struct RankingLimitQuery {
+    limit: Option<usize>,
+}
+
+async fn snapshot_of<Stats, Repository>(
+    repository: &Repository,
+    players: Option<HashSet<Player>>,
+    condition: TimeBasedSnapshotSearchCondition,
+) -> Result<SnapshotResponse, ApiError>
+where
+    Stats: NumericStats + Send + 'static,
+    Repository: PlayerTimedStatsRepository<Stats> + Sync,
+{
+    let snapshot = match players {
+        Some(players) => {
+            GetSnapshotOfPlayers::new(repository)
+                .execute::<Stats>(players, condition)
+                .await?
+        }
+        None => {
+            GetSnapshot::new(repository)
+                .execute::<Stats>(condition)
+                .await?
+        }
+    }
+    .ok_or_else(|| ApiError::NotFound("no snapshot matches the condition".to_owned()))?;
+
+    Ok(snapshot_to_response(&snapshot)?)
+}
+
+async fn player_stats_history_of<Stats, Repository>(
+    repository: &Repository,
+    player: Player,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> Result<PlayerStatsHistoryResponse, ApiError>
+where
+    Stats: NumericStats + Send + 'static,
+    Repository: PlayerTimedStatsRepository<Stats> + Sync,
+{
+    let history = GetPlayerStatsHistory::new(repository)
+        .execute::<Stats>(player, from, to)
+        .await?;
+
+    Ok(player_stats_history_to_response(history))
+}
+
+async fn resampled_player_stats_of<Stats, Repository>(
+    repository: &Repository,
+    players: HashSet<Player>,
+    sampling: TimeSeriesSampling,
+) -> Result<ResampledPlayerStatsResponse, ApiError>
+where
+    Stats: NumericStats + Send + 'static,
+    Repository: PlayerTimedStatsRepository<Stats> + Sync,
+{
+    let samples = GetResampledPlayerStats::new(repository)
+        .execute::<Stats>(players, sampling)
+        .await?;
+
+    Ok(resampled_player_stats_to_response(samples)?)
+}
+
+async fn player_rank_history_of<Stats, Repository>(
+    repository: &Repository,
+    player: Player,
+    points: TimeSeriesPoints,
+) -> Result<PlayerRankHistoryResponse, ApiError>
+where
+    Stats: Send + 'static,
+    Repository: PlayerTimedStatsRepository<Stats> + Sync,
+{
+    let history = GetPlayerRankHistory::new(repository)
+        .execute::<Stats>(player, points)
+        .await?;
+
+    Ok(player_rank_history_to_response(history))
+}
+
+async fn snapshot_summary_of<Stats, Repository>(
+    repository: &Repository,
+    condition: TimeBasedSnapshotSearchCondition,
+    percentages: &[f64],
+) -> Result<SnapshotSummaryResponse, ApiError>
+where
+    Stats: NumericStats + Send + 'static,
+    Repository: PlayerTimedStatsRepository<Stats> + Sync,
+{
+    let summary = GetSnapshotSummary::new(repository)
+        .execute::<Stats>(condition, percentages)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("no snapshot matches the condition".to_owned()))?;
+
+    Ok(snapshot_summary_to_response(summary))
+}
+
+async fn snapshot_comparison_of<Stats, Repository>(
+    repository: &Repository,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> Result<SnapshotComparisonResponse, ApiError>
+where
+    Stats: NumericStats + Clone + Eq + Send + 'static,
+    Repository: PlayerTimedStatsRepository<Stats> + Sync,
+{
+    let comparison = CompareSnapshots::new(repository)
+        .execute::<Stats>(from, to)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("no snapshot is recorded before `to`".to_owned()))?;
+
+    Ok(snapshot_comparison_to_response(comparison)?)
+}
+
+async fn ranking_of<Stats, Repository>(
+    repository: &Repository,
+    condition: TimeBasedSnapshotSearchCondition,
+    limit: usize,
+) -> Result<RankingResponse, ApiError>
+where
+    Stats: NumericStats + Ord + Clone + Send + 'static,
+    Repository: PlayerTimedStatsRepository<Stats> + Sync,
+{
+    let ranking = GetRanking::new(repository)
+        .execute::<Stats>(condition, limit)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("no snapshot matches the condition".to_owned()))?;
+
+    Ok(ranking_to_response(ranking)?)
+}
+
+#[tracing::instrument(skip(repository))]
+async fn get_snapshot<Repository: TimedStatsRepository>(
+    State(repository): State<Arc<Repository>>,
+    Path(stats_kind): Path<StatsKind>,
+    Query(condition): Query<SnapshotConditionQuery>,
+    Query(players): Query<SnapshotPlayersQuery>,
+) -> Result<Json<SnapshotResponse>, ApiError> {
+    let condition = condition.into_condition()?;
+    let players = players
+        .players
+        .as_deref()
+        .map(players_from_query)
+        .transpose()?;
+
+    let response = with_stats_type!(usecases::StatsKind::from(stats_kind), Stats => {
+        snapshot_of::<Stats, _>(repository.as_ref(), players, condition).await
+    })?;
+
+    Ok(Json(response))
+}
+
+#[tracing::instrument(skip(repository))]
+async fn get_player_stats_history<Repository: TimedStatsRepository>(
+    State(repository): State<Arc<Repository>>,
+    Path((stats_kind, player_uuid)): Path<(StatsKind, String)>,
+    Query(range): Query<TimeRangeQuery>,
+) -> Result<Json<PlayerStatsHistoryResponse>, ApiError> {
+    let player = player_from_path(&player_uuid)?;
+    let from = parse_timestamp(&range.from, "from")?;
+    let to = parse_timestamp(&range.to, "to")?;
+
+    let response = with_stats_type!(usecases::StatsKind::from(stats_kind), Stats => {
+        player_stats_history_of::<Stats, _>(repository.as_ref(), player, from, to).await
+    })?;
+
+    Ok(Json(response))
+}
+
+#[tracing::instrument(skip(repository))]
+async fn get_resampled_player_stats<Repository: TimedStatsRepository>(
+    State(repository): State<Arc<Repository>>,
+    Path(stats_kind): Path<StatsKind>,
+    Query(query): Query<ResampledPlayerStatsQuery>,
+) -> Result<Json<ResampledPlayerStatsResponse>, ApiError> {
+    let players = players_from_query(&query.players)?;
+    let sampling = TimeSeriesSampling::new(
+        parse_timestamp(&query.from, "from")?,
+        parse_timestamp(&query.to, "to")?,
+        Duration::seconds(i64::from(query.interval_seconds)),
+    )
+    .map_err(|error| ApiError::BadRequest(error.to_string()))?;
+
+    let response = with_stats_type!(usecases::StatsKind::from(stats_kind), Stats => {
+        resampled_player_stats_of::<Stats, _>(repository.as_ref(), players, sampling).await
+    })?;
+
+    Ok(Json(response))
+}
+
+#[tracing::instrument(skip(repository))]
+async fn get_player_rank_history<Repository: TimedStatsRepository>(
+    State(repository): State<Arc<Repository>>,
+    Path((stats_kind, player_uuid)): Path<(StatsKind, String)>,
+    Query(query): Query<PlayerRankHistoryQuery>,
+) -> Result<Json<PlayerRankHistoryResponse>, ApiError> {
+    let player = player_from_path(&player_uuid)?;
+    let from = parse_timestamp(&query.from, "from")?;
+    let to = parse_timestamp(&query.to, "to")?;
+    let points = match query.interval_seconds {
+        Some(interval_seconds) => TimeSeriesPoints::Sampled(
+            TimeSeriesSampling::new(from, to, Duration::seconds(i64::from(interval_seconds)))
+                .map_err(|error| ApiError::BadRequest(error.to_string()))?,
+        ),
+        None => TimeSeriesPoints::Recorded { from, to },
+    };
+
+    let response = with_stats_type!(usecases::StatsKind::from(stats_kind), Stats => {
+        player_rank_history_of::<Stats, _>(repository.as_ref(), player, points).await
+    })?;
+
+    Ok(Json(response))
+}
+
+#[tracing::instrument(skip(repository))]
+async fn get_snapshot_summary<Repository: TimedStatsRepository>(
+    State(repository): State<Arc<Repository>>,
+    Path(stats_kind): Path<StatsKind>,
+    Query(condition): Query<SnapshotConditionQuery>,
+    Query(summary): Query<SnapshotSummaryQuery>,
+) -> Result<Json<SnapshotSummaryResponse>, ApiError> {
+    let condition = condition.into_condition()?;
+    let percentages = summary.percentages()?;
+
+    let response = with_stats_type!(usecases::StatsKind::from(stats_kind), Stats => {
+        snapshot_summary_of::<Stats, _>(repository.as_ref(), condition, &percentages).await
+    })?;
+
+    Ok(Json(response))
+}
+
+#[tracing::instrument(skip(repository))]
+async fn compare_snapshots<Repository: TimedStatsRepository>(
+    State(repository): State<Arc<Repository>>,
+    Path(stats_kind): Path<StatsKind>,
+    Query(range): Query<TimeRangeQuery>,
+) -> Result<Json<SnapshotComparisonResponse>, ApiError> {
+    let from = parse_timestamp(&range.from, "from")?;
+    let to = parse_timestamp(&range.to, "to")?;
+
+    let response = with_stats_type!(usecases::StatsKind::from(stats_kind), Stats => {
+        snapshot_comparison_of::<Stats, _>(repository.as_ref(), from, to).await
+    })?;
+
+    Ok(Json(response))
+}
+
+#[tracing::instrument(skip(repository))]
+async fn get_ranking<Repository: TimedStatsRepository>(
+    State(repository): State<Arc<Repository>>,
+    Path(stats_kind): Path<StatsKind>,
+    Query(condition): Query<SnapshotConditionQuery>,
+    Query(limit): Query<RankingLimitQuery>,
+) -> Result<Json<RankingResponse>, ApiError> {
+    let condition = condition.into_condition()?;
+    let limit = match limit.limit {
+        Some(0) => return Err(ApiError::BadRequest("`limit` must be positive".to_owned())),
+        Some(limit) => limit,
+        None => DEFAULT_RANKING_LIMIT,
+    };
+
+    let response = with_stats_type!(usecases::StatsKind::from(stats_kind), Stats => {
+        ranking_of::<Stats, _>(repository.as_ref(), condition, limit).await
+    })?;
+
+    Ok(Json(response))
+}
+
+pub fn router<Repository: TimedStatsRepository>(repository: Repository) -> Router {
+    Router::new()
+        .route("/v1/:stats_kind/snapshot", get(get_snapshot::<Repository>))
+        .route(
+            "/v1/:stats_kind/players/:player_uuid/history",
+            get(get_player_stats_history::<Repository>),
+        )
+        .route(
+            "/v1/:stats_kind/players/:player_uuid/rank-history",
+            get(get_player_rank_history::<Repository>),
+        )
+        .route(
+            "/v1/:stats_kind/resampled",
+            get(get_resampled_player_stats::<Repository>),
+        )
+        .route(
+            "/v1/:stats_kind/summary",
+            get(get_snapshot_summary::<Repository>),
+        )
+        .route(
+            "/v1/:stats_kind/compare",
+            get(compare_snapshots::<Repository>),
+        )
+        .route("/v1/:stats_kind/ranking", get(get_ranking::<Repository>))
+        .with_state(Arc::new(repository))
+}
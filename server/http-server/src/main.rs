@@ -0,0 +1,84 @@
+#![deny(clippy::all, clippy::cargo)]
+#![warn(clippy::nursery, clippy::pedantic)]
+#![allow(clippy::cargo_common_metadata)]
+
+use std::net::SocketAddr;
+use std::time::Duration;
+
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::Layer;
+
+use infra_db_repository_impl::{config::Database, DatabaseConnector};
+
+use crate::config::{HTTP_SERVER_CONFIG, SENTRY_CONFIG};
+
+mod config;
+mod error;
+mod handlers;
+mod responses;
+
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        tokio::signal::ctrl_c()
+            .await
+            .expect("failed to install Ctrl+C handler");
+    };
+
+    let terminate = async {
+        tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+            .expect("failed to install SIGTERM handler")
+            .recv()
+            .await;
+    };
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
+
+    tracing::info!("Shutting down...");
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // initialize tracing
+    // see https://github.com/tokio-rs/axum/blob/79a0a54bc9f0f585c974b5e6793541baff980662/examples/tracing-aka-logging/src/main.rs
+    tracing_subscriber::registry()
+        .with(sentry::integrations::tracing::layer())
+        .with(
+            tracing_subscriber::fmt::layer().with_filter(tracing_subscriber::EnvFilter::new(
+                std::env::var("RUST_LOG").unwrap_or_else(|_| "info".into()),
+            )),
+        )
+        .init();
+
+    // setup sentry
+    // only send sentry events when we are not running locally
+    let _sentry_client_guard = if SENTRY_CONFIG.environment_name != "local" {
+        Some(sentry::init((
+            SENTRY_CONFIG.dsn.clone(),
+            sentry::ClientOptions {
+                release: sentry::release_name!(),
+                traces_sample_rate: 0.1,
+                environment: Some(SENTRY_CONFIG.environment_name.clone().into()),
+                shutdown_timeout: Duration::from_secs(10),
+                ..Default::default()
+            },
+        )))
+    } else {
+        None
+    };
+
+    let repository = DatabaseConnector::try_new(Database::from_env()?).await?;
+
+    let address = SocketAddr::from(([0, 0, 0, 0], HTTP_SERVER_CONFIG.port));
+    tracing::info!("Listening on {address}");
+
+    axum::Server::bind(&address)
+        .serve(handlers::router(repository).into_make_service())
+        .with_graceful_shutdown(shutdown_signal())
+        .await?;
+
+    Ok(())
+}
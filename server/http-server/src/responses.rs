@@ -0,0 +1,260 @@
+use chrono::{DateTime, SecondsFormat, Utc};
+use std::collections::HashMap;
+
+use domain::models::{
+    NumericStats, Player, RankedPlayer, Ranking, ResampledStats, SnapshotComparison,
+    SnapshotSummary, StatsSnapshot, TimestampedRank, TimestampedStats,
+};
+
+/// `timestamp` を RFC 3339 形式の文字列に変換する。
+fn rfc3339(timestamp: &DateTime<Utc>) -> String {
+    timestamp.to_rfc3339_opts(SecondsFormat::AutoSi, true)
+}
+
+#[derive(serde::Serialize, Debug)]
+pub struct PlayerStatsValue {
+    pub player_uuid: String,
+    pub value: u64,
+}
+
+#[derive(serde::Serialize, Debug)]
+pub struct SnapshotResponse {
+    pub timestamp: String,
+    pub player_stats: Vec<PlayerStatsValue>,
+}
+
+#[derive(serde::Serialize, Debug)]
+pub struct TimestampedValue {
+    pub timestamp: String,
+    pub value: u64,
+}
+
+#[derive(serde::Serialize, Debug)]
+pub struct PlayerStatsHistoryResponse {
+    /// 時刻の昇順に並んだ、プレーヤーの統計量の履歴。
+    pub history: Vec<TimestampedValue>,
+}
+
+#[derive(serde::Serialize, Debug)]
+pub struct ResampledPlayerStats {
+    pub sample_timestamp: String,
+    /// 標本の値として用いたデータ点の時刻。そのようなデータ点が存在しない場合は `null` となる。
+    pub snapshot_timestamp: Option<String>,
+    pub player_stats: Vec<PlayerStatsValue>,
+}
+
+#[derive(serde::Serialize, Debug)]
+pub struct ResampledPlayerStatsResponse {
+    /// 時刻の昇順に並んだ標本。
+    pub samples: Vec<ResampledPlayerStats>,
+}
+
+#[derive(serde::Serialize, Debug)]
+pub struct RankedPlayerValue {
+    pub rank: u64,
+    pub player_uuid: String,
+    pub value: u64,
+}
+
+#[derive(serde::Serialize, Debug)]
+pub struct RankingResponse {
+    pub timestamp: String,
+    /// ランキングの対象となったプレーヤーの総数。
+    pub total_player_count: u64,
+    /// 順位の昇順に並んだ上位プレーヤー。
+    pub top_players: Vec<RankedPlayerValue>,
+}
+
+#[derive(serde::Serialize, Debug)]
+pub struct TimestampedRankValue {
+    pub timestamp: String,
+    /// 順位の計算に用いたデータ点の時刻。そのようなデータ点が存在しない場合は `null` となる。
+    pub snapshot_timestamp: Option<String>,
+    /// 1 から始まる順位。プレーヤーがデータ点に含まれていない場合は `null` となる。
+    pub rank: Option<u64>,
+    pub total_ranked_player_count: u64,
+}
+
+#[derive(serde::Serialize, Debug)]
+pub struct PlayerRankHistoryResponse {
+    /// 時刻の昇順に並んだ、プレーヤーの順位の推移。
+    pub history: Vec<TimestampedRankValue>,
+}
+
+#[derive(serde::Serialize, Debug)]
+pub struct PercentileValue {
+    pub percentage: f64,
+    pub value: u64,
+}
+
+#[derive(serde::Serialize, Debug)]
+pub struct SnapshotSummaryResponse {
+    pub timestamp: String,
+    pub player_count: u64,
+    /// 統計量の総和。 `u64` の範囲を超える場合は `u64::MAX` に飽和する。
+    pub sum: u64,
+    /// スナップショットにプレーヤーが一人も含まれていない場合は `null` となる。
+    pub mean: Option<f64>,
+    pub median: Option<f64>,
+    pub min: Option<u64>,
+    pub max: Option<u64>,
+    /// 要求された順に並んだ百分位数。
+    pub percentiles: Vec<PercentileValue>,
+}
+
+#[derive(serde::Serialize, Debug)]
+pub struct PlayerStatsChangeValue {
+    pub player_uuid: String,
+    /// 古い方のスナップショットでの値。プレーヤーが含まれていなかった場合は `null` となる。
+    pub old_value: Option<u64>,
+    /// 新しい方のスナップショットでの値。プレーヤーが含まれなくなった場合は `null` となる。
+    pub new_value: Option<u64>,
+}
+
+#[derive(serde::Serialize, Debug)]
+pub struct SnapshotComparisonResponse {
+    /// 古い方のスナップショットの時刻。存在しなかった場合は `null` となる。
+    pub from_snapshot_timestamp: Option<String>,
+    pub to_snapshot_timestamp: String,
+    /// UUID の昇順に並んだ、値が変化したか、現れたか、含まれなくなったプレーヤーの変化。
+    pub changes: Vec<PlayerStatsChangeValue>,
+}
+
+fn player_uuid_to_response(player: &Player) -> anyhow::Result<String> {
+    Ok(player.uuid.as_str()?.to_owned())
+}
+
+fn player_stats_to_response<Stats: NumericStats>(
+    player_stats: &HashMap<Player, Stats>,
+) -> anyhow::Result<Vec<PlayerStatsValue>> {
+    player_stats
+        .iter()
+        .map(|(player, stats)| {
+            anyhow::Ok(PlayerStatsValue {
+                player_uuid: player_uuid_to_response(player)?,
+                value: stats.raw_value(),
+            })
+        })
+        .collect()
+}
+
+pub fn snapshot_to_response<Stats: NumericStats>(
+    snapshot: &StatsSnapshot<Stats>,
+) -> anyhow::Result<SnapshotResponse> {
+    Ok(SnapshotResponse {
+        timestamp: rfc3339(&snapshot.utc_timestamp),
+        player_stats: player_stats_to_response(&snapshot.player_stats)?,
+    })
+}
+
+pub fn resampled_player_stats_to_response<Stats: NumericStats>(
+    samples: Vec<ResampledStats<Stats>>,
+) -> anyhow::Result<ResampledPlayerStatsResponse> {
+    let samples = samples
+        .into_iter()
+        .map(|sample| {
+            anyhow::Ok(ResampledPlayerStats {
+                sample_timestamp: rfc3339(&sample.sample_utc_timestamp),
+                snapshot_timestamp: sample.snapshot_utc_timestamp.as_ref().map(rfc3339),
+                player_stats: player_stats_to_response(&sample.player_stats)?,
+            })
+        })
+        .collect::<anyhow::Result<_>>()?;
+
+    Ok(ResampledPlayerStatsResponse { samples })
+}
+
+pub fn player_stats_history_to_response<Stats: NumericStats>(
+    history: Vec<TimestampedStats<Stats>>,
+) -> PlayerStatsHistoryResponse {
+    PlayerStatsHistoryResponse {
+        history: history
+            .into_iter()
+            .map(|timestamped_stats| TimestampedValue {
+                timestamp: rfc3339(&timestamped_stats.utc_timestamp),
+                value: timestamped_stats.stats.raw_value(),
+            })
+            .collect(),
+    }
+}
+
+pub fn player_rank_history_to_response(history: Vec<TimestampedRank>) -> PlayerRankHistoryResponse {
+    PlayerRankHistoryResponse {
+        history: history
+            .into_iter()
+            .map(|timestamped_rank| TimestampedRankValue {
+                timestamp: rfc3339(&timestamped_rank.utc_timestamp),
+                snapshot_timestamp: timestamped_rank
+                    .snapshot_utc_timestamp
+                    .as_ref()
+                    .map(rfc3339),
+                rank: timestamped_rank.rank,
+                total_ranked_player_count: timestamped_rank.total_ranked_player_count,
+            })
+            .collect(),
+    }
+}
+
+pub fn snapshot_summary_to_response(summary: SnapshotSummary) -> SnapshotSummaryResponse {
+    SnapshotSummaryResponse {
+        timestamp: rfc3339(&summary.utc_timestamp),
+        player_count: summary.player_count,
+        sum: summary.sum,
+        mean: summary.mean,
+        median: summary.median,
+        min: summary.min,
+        max: summary.max,
+        percentiles: summary
+            .percentiles
+            .into_iter()
+            .map(|percentile| PercentileValue {
+                percentage: percentile.percentage,
+                value: percentile.value,
+            })
+            .collect(),
+    }
+}
+
+pub fn snapshot_comparison_to_response<Stats: NumericStats>(
+    comparison: SnapshotComparison<Stats>,
+) -> anyhow::Result<SnapshotComparisonResponse> {
+    let changes = comparison
+        .changes
+        .into_iter()
+        .map(|change| {
+            anyhow::Ok(PlayerStatsChangeValue {
+                player_uuid: player_uuid_to_response(&change.player)?,
+                old_value: change.old_stats.as_ref().map(NumericStats::raw_value),
+                new_value: change.new_stats.as_ref().map(NumericStats::raw_value),
+            })
+        })
+        .collect::<anyhow::Result<_>>()?;
+
+    Ok(SnapshotComparisonResponse {
+        from_snapshot_timestamp: comparison.old_utc_timestamp.as_ref().map(rfc3339),
+        to_snapshot_timestamp: rfc3339(&comparison.new_utc_timestamp),
+        changes,
+    })
+}
+
+pub fn ranking_to_response<Stats: NumericStats>(
+    ranking: Ranking<Stats>,
+) -> anyhow::Result<RankingResponse> {
+    let top_players = ranking
+        .top_players
+        .into_iter()
+        .map(|ranked_player: RankedPlayer<Stats>| {
+            anyhow::Ok(RankedPlayerValue {
+                rank: ranked_player.rank,
+                player_uuid: player_uuid_to_response(&ranked_player.player)?,
+                value: ranked_player.value.raw_value(),
+            })
+        })
+        .collect::<anyhow::Result<_>>()?;
+
+    Ok(RankingResponse {
+        timestamp: rfc3339(&ranking.utc_timestamp),
+        total_player_count: ranking.total_player_count,
+        top_players,
+    })
+}
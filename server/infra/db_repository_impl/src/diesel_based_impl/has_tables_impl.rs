@@ -2,8 +2,9 @@ use chrono::NaiveDateTime;
 use chrono::TimeZone;
 use chrono::{DateTime, Utc};
 use diesel::mysql::Mysql;
-use diesel::query_dsl::methods::*;
+use diesel::sql_types::{Integer, Nullable, Text};
 use diesel::ExpressionMethods;
+use diesel::QueryDsl;
 use diesel_async::{AsyncConnection, RunQueryDsl};
 use domain::models::{BreakCount, BuildCount, PlayTicks, Player, VoteCount};
 use std::collections::{HashMap, HashSet};
@@ -13,9 +14,10 @@ use anyhow::anyhow;
 use crate::debugging_utils::display_diff_point_ids_for_tracing;
 use crate::structures_embedded_in_rdb::{
     DiffPoint, DiffPointId, FullSnapshotPoint, IdIndexedDiffPoints, SnapshotDiff,
+    SnapshotPointHeader, SnapshotPointReference,
 };
-use crate::TimeBasedSnapshotSearchCondition::{NewestBefore, OldestAfter};
-use domain::models::{PlayerUuidString, StatsSnapshot};
+use crate::TimeBasedSnapshotSearchCondition::{AtPoint, Exact, Nearest, NewestBefore, OldestAfter};
+use domain::models::{PlayerUuidString, SnapshotPointId, StatsSnapshot};
 use domain::repositories::TimeBasedSnapshotSearchCondition;
 
 use super::schema;
@@ -43,6 +45,191 @@ impl_from_value_column!(BuildCount, BuildCount, u64);
 impl_from_value_column!(PlayTicks, PlayTicks, u64);
 impl_from_value_column!(VoteCount, VoteCount, u64);
 
+/// `newest_before` と `oldest_after` のうち、時刻が `timestamp` により近い方を返す。
+/// 等しく近い場合は `newest_before` の方を返す。
+fn closer_to<Id>(
+    timestamp: NaiveDateTime,
+    newest_before: Option<(Id, NaiveDateTime)>,
+    oldest_after: Option<(Id, NaiveDateTime)>,
+) -> Option<(Id, NaiveDateTime)> {
+    match (newest_before, oldest_after) {
+        (Some(before), Some(after)) => {
+            if after.1 - timestamp < timestamp - before.1 {
+                Some(after)
+            } else {
+                Some(before)
+            }
+        }
+        (before, None) => before,
+        (None, after) => after,
+    }
+}
+
+/// diff point テーブルから読み出した各データ点の直前のデータ点と時刻、
+/// および diff テーブルから読み出した差分レコードから、データ点を組み立てる。
+///
+/// 直前のデータ点から統計量が一つも変化しなかったデータ点は差分レコードを持たないため、
+/// 差分レコードではなく `diff_point_data_map` を基準に組み立て、そのようなデータ点には空の差分を持たせる。
+fn assemble_diff_points<Stats: Clone>(
+    diff_point_data_map: HashMap<DiffPointId, (Option<DiffPointId>, NaiveDateTime)>,
+    diffs: Vec<(DiffPointId, PlayerUuidString, Option<Stats>)>,
+) -> IdIndexedDiffPoints<Stats> {
+    let mut player_stats_diffs_by_point = HashMap::new();
+    for (diff_point_id, uuid, new_value) in diffs {
+        player_stats_diffs_by_point
+            .entry(diff_point_id)
+            .or_insert_with(HashMap::new)
+            .insert(uuid, new_value);
+    }
+
+    IdIndexedDiffPoints::new(
+        diff_point_data_map
+            .into_iter()
+            .map(
+                |(diff_point_id, (previous_diff_point_id, timestamp))| DiffPoint {
+                    id: diff_point_id,
+                    previous_diff_point_id,
+                    diff: SnapshotDiff {
+                        utc_timestamp: Utc.from_utc_datetime(&timestamp),
+                        player_stats_diffs: player_stats_diffs_by_point
+                            .remove(&diff_point_id)
+                            .unwrap_or_default(),
+                    },
+                },
+            )
+            .collect(),
+    )
+}
+
+/// `player_uuids` のうち `player` テーブルに登録されているプレーヤーの ID を返す。
+async fn find_player_ids<Connection: AsyncConnection<Backend = Mysql> + Send>(
+    player_uuids: &[PlayerUuidString],
+    conn: &mut Connection,
+) -> anyhow::Result<HashMap<PlayerUuidString, u32>> {
+    use schema::player::dsl;
+
+    let mut player_ids = HashMap::with_capacity(player_uuids.len());
+    // プレースホルダー数が 65536 を超えないように 20000 件ずつに分割する
+    for player_uuids in player_uuids.chunks(20000) {
+        let player_uuids = player_uuids
+            .iter()
+            .map(PlayerUuidString::as_str)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let records = dsl::player
+            .select((dsl::id, dsl::uuid))
+            .filter(dsl::uuid.eq_any(player_uuids))
+            .load::<(u32, String)>(conn)
+            .await?;
+
+        for (id, uuid) in records {
+            player_ids.insert(PlayerUuidString::from_string(&uuid)?, id);
+        }
+    }
+
+    Ok(player_ids)
+}
+
+/// `player_uuids` のそれぞれに対応する `player` テーブル上の ID を返す。
+/// `player` テーブルにまだ登録されていないプレーヤーは、新たに登録した上で ID を返す。
+#[tracing::instrument(skip(player_uuids, conn), fields(player_count = player_uuids.len()))]
+async fn intern_players<Connection: AsyncConnection<Backend = Mysql> + Send>(
+    player_uuids: Vec<PlayerUuidString>,
+    conn: &mut Connection,
+) -> anyhow::Result<HashMap<PlayerUuidString, u32>> {
+    let mut player_ids = find_player_ids(&player_uuids, conn).await?;
+
+    // 登録済みのプレーヤーまで挿入を試みると、 INSERT IGNORE であっても auto_increment の値が消費されてしまうため、
+    // 未登録のプレーヤーのみを挿入する
+    let unregistered_player_uuids = player_uuids
+        .into_iter()
+        .filter(|player_uuid| !player_ids.contains_key(player_uuid))
+        .collect::<Vec<_>>();
+    if unregistered_player_uuids.is_empty() {
+        return Ok(player_ids);
+    }
+
+    {
+        use schema::player::dsl;
+        for player_uuids in unregistered_player_uuids.chunks(20000) {
+            let records_to_insert = player_uuids
+                .iter()
+                .map(|player_uuid| anyhow::Ok(dsl::uuid.eq(player_uuid.as_str()?)))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            // 別の接続が同じプレーヤーを同時に登録した場合に備えて、重複は無視する
+            diesel::insert_or_ignore_into(dsl::player)
+                .values(records_to_insert)
+                .execute(conn)
+                .await?;
+        }
+    }
+
+    player_ids.extend(find_player_ids(&unregistered_player_uuids, conn).await?);
+
+    Ok(player_ids)
+}
+
+diesel::sql_function!(fn last_insert_id() -> diesel::sql_types::Unsigned<diesel::sql_types::BigInt>);
+
+/// `conn` 上で直前に挿入されたレコードの auto_increment な ID を返す。
+/// `LAST_INSERT_ID()` は接続ごとに管理されるため、他の接続による挿入の影響を受けない。
+async fn fetch_last_insert_id<Connection: AsyncConnection<Backend = Mysql> + Send>(
+    conn: &mut Connection,
+) -> anyhow::Result<u64> {
+    Ok(diesel::select(last_insert_id())
+        .get_result::<u64>(conn)
+        .await?)
+}
+
+/// 書き込み用のロックの取得を待つ最大の秒数。
+const WRITER_LOCK_TIMEOUT_SECONDS: i32 = 600;
+
+#[derive(diesel::QueryableByName)]
+struct LockFunctionResult {
+    #[diesel(sql_type = Nullable<Integer>)]
+    result: Option<i32>,
+}
+
+/// `conn` のセッションで、データベースごとに `lock_name` という名前のロックを取得する。
+/// ロックは `release_named_lock` を呼ぶか、セッションが終了するまで保持される。
+async fn acquire_named_lock<Connection: AsyncConnection<Backend = Mysql> + Send>(
+    lock_name: &str,
+    conn: &mut Connection,
+) -> anyhow::Result<()> {
+    let acquired = diesel::sql_query("SELECT GET_LOCK(CONCAT(DATABASE(), '.', ?), ?) AS result")
+        .bind::<Text, _>(lock_name)
+        .bind::<Integer, _>(WRITER_LOCK_TIMEOUT_SECONDS)
+        .get_result::<LockFunctionResult>(conn)
+        .await?
+        .result;
+
+    match acquired {
+        Some(1) => Ok(()),
+        Some(_) => Err(anyhow!(
+            "timed out after {WRITER_LOCK_TIMEOUT_SECONDS} seconds while waiting for lock {lock_name}"
+        )),
+        None => Err(anyhow!("failed to acquire lock {lock_name}")),
+    }
+}
+
+/// `acquire_named_lock` によって取得したロックを解放する。
+async fn release_named_lock<Connection: AsyncConnection<Backend = Mysql> + Send>(
+    lock_name: &str,
+    conn: &mut Connection,
+) -> anyhow::Result<()> {
+    let released = diesel::sql_query("SELECT RELEASE_LOCK(CONCAT(DATABASE(), '.', ?)) AS result")
+        .bind::<Text, _>(lock_name)
+        .get_result::<LockFunctionResult>(conn)
+        .await?
+        .result;
+
+    match released {
+        Some(1) => Ok(()),
+        _ => Err(anyhow!("lock {lock_name} was not held by this session")),
+    }
+}
+
 macro_rules! impl_has_incremental_snapshot_tables {
     ($stats_type:ty,
      $full_snapshot_point_table:ident,
@@ -54,22 +241,28 @@ macro_rules! impl_has_incremental_snapshot_tables {
             HasIncrementalSnapshotTables<Connection> for $stats_type
         {
             #[tracing::instrument(skip(conn))]
-            async fn create_full_snapshot_point(conn: &mut Connection) -> anyhow::Result<u64> {
+            async fn acquire_writer_lock(conn: &mut Connection) -> anyhow::Result<()> {
+                acquire_named_lock(concat!("snapshot_writer.", stringify!($stats_type)), conn).await
+            }
+
+            #[tracing::instrument(skip(conn))]
+            async fn release_writer_lock(conn: &mut Connection) -> anyhow::Result<()> {
+                release_named_lock(concat!("snapshot_writer.", stringify!($stats_type)), conn).await
+            }
+
+            #[tracing::instrument(skip(conn))]
+            async fn create_full_snapshot_point(
+                timestamp: DateTime<Utc>,
+                conn: &mut Connection,
+            ) -> anyhow::Result<u64> {
                 {
                     use schema::$full_snapshot_point_table::dsl;
                     diesel::insert_into(dsl::$full_snapshot_point_table)
-                        .values(dsl::record_timestamp.eq(Utc::now().naive_utc()))
+                        .values(dsl::record_timestamp.eq(timestamp.naive_utc()))
                         .execute(conn)
                         .await?;
                 }
-                let created_full_snapshot_point_id = {
-                    use schema::$full_snapshot_point_table::dsl;
-                    dsl::$full_snapshot_point_table
-                        .select(dsl::id)
-                        .order(dsl::id.desc())
-                        .first::<u64>(conn)
-                        .await?
-                };
+                let created_full_snapshot_point_id = fetch_last_insert_id(conn).await?;
 
                 Ok(created_full_snapshot_point_id)
             }
@@ -80,17 +273,21 @@ macro_rules! impl_has_incremental_snapshot_tables {
                 player_stats: HashMap<Player, Self>,
                 conn: &mut Connection,
             ) -> anyhow::Result<()> {
+                let player_ids =
+                    intern_players(player_stats.keys().map(|player| player.uuid).collect(), conn)
+                        .await?;
+
                 use schema::$full_snapshot_dsl_table::dsl;
                 let records_to_insert = player_stats
                     .iter()
                     .map(|(player, stats)| {
-                        anyhow::Ok((
+                        (
                             dsl::full_snapshot_point_id.eq(fresh_full_snapshot_point_id),
-                            dsl::player_uuid.eq(player.uuid.as_str()?),
+                            dsl::player_id.eq(player_ids[&player.uuid]),
                             dsl::value.eq(stats.0),
-                        ))
+                        )
                     })
-                    .collect::<Result<Vec<_>, _>>()?;
+                    .collect::<Vec<_>>();
 
                 // プレースホルダー数が 65536 を超えないように 20000 件ずつに分割する
                 for records in records_to_insert.chunks(20000) {
@@ -121,34 +318,30 @@ macro_rules! impl_has_incremental_snapshot_tables {
                         .execute(conn)
                         .await?;
                 }
-                let created_diff_snapshot_point_id = {
-                    use schema::$diff_point_table::dsl;
-                    dsl::$diff_point_table
-                        .select(dsl::id)
-                        .order(dsl::id.desc())
-                        .first::<u64>(conn)
-                        .await?
-                };
+                let created_diff_snapshot_point_id = fetch_last_insert_id(conn).await?;
                 Ok(DiffPointId(created_diff_snapshot_point_id))
             }
 
             #[tracing::instrument(skip(conn, player_stats_diffs))]
             async fn insert_all_stats_at_diff_snapshot_point(
                 fresh_diff_snapshot_point_id: DiffPointId,
-                player_stats_diffs: HashMap<PlayerUuidString, Self>,
+                player_stats_diffs: HashMap<PlayerUuidString, Option<Self>>,
                 conn: &mut Connection,
             ) -> anyhow::Result<()> {
+                let player_ids =
+                    intern_players(player_stats_diffs.keys().copied().collect(), conn).await?;
+
                 use schema::$diff_table::dsl;
                 let records_to_insert = player_stats_diffs
                     .iter()
                     .map(|(player_uuid, stats)| {
-                        anyhow::Ok((
+                        (
                             dsl::diff_point_id.eq(fresh_diff_snapshot_point_id.0),
-                            dsl::player_uuid.eq(player_uuid.as_str()?),
-                            dsl::new_value.eq(stats.0),
-                        ))
+                            dsl::player_id.eq(player_ids[player_uuid]),
+                            dsl::new_value.eq(stats.as_ref().map(|stats| stats.0)),
+                        )
                     })
-                    .collect::<Result<Vec<_>, _>>()?;
+                    .collect::<Vec<_>>();
 
                 // プレースホルダー数が 65536 を超えないように 20000 件ずつに分割する
                 for records in records_to_insert.chunks(20000) {
@@ -178,7 +371,8 @@ macro_rules! impl_has_incremental_snapshot_tables {
                 let player_stats = {
                     use schema::$full_snapshot_dsl_table::dsl;
                     dsl::$full_snapshot_dsl_table
-                        .select((dsl::player_uuid, dsl::value))
+                        .inner_join(schema::player::table)
+                        .select((schema::player::uuid, dsl::value))
                         .filter(dsl::full_snapshot_point_id.eq(full_snapshot_point_id))
                         .load::<(String, u64)>(conn)
                 }
@@ -237,9 +431,10 @@ macro_rules! impl_has_incremental_snapshot_tables {
                 let diffs = {
                     use schema::$diff_table::dsl;
                     dsl::$diff_table
-                        .select((dsl::diff_point_id, dsl::player_uuid, dsl::new_value))
+                        .inner_join(schema::player::table)
+                        .select((dsl::diff_point_id, schema::player::uuid, dsl::new_value))
                         .filter(dsl::diff_point_id.eq_any(&diff_snapshot_point_ids))
-                        .load::<(DiffPointId, String, u64)>(conn)
+                        .load::<(DiffPointId, String, Option<u64>)>(conn)
                 }
                 .await?
                 .into_iter()
@@ -247,37 +442,12 @@ macro_rules! impl_has_incremental_snapshot_tables {
                     anyhow::Ok((
                         point_id,
                         PlayerUuidString::from_string(&uuid)?,
-                        Self::from_value_column(value),
+                        value.map(Self::from_value_column),
                     ))
                 })
                 .collect::<Result<Vec<_>, _>>()?;
 
-                let mut diff_points = HashMap::new();
-                for (diff_point_id, uuid, new_value) in diffs {
-                    diff_points
-                        .entry(diff_point_id)
-                        .or_insert_with(|| HashMap::new())
-                        .insert(uuid, new_value);
-                }
-
-                Ok(IdIndexedDiffPoints::new(
-                    diff_points
-                        .into_iter()
-                        .map(|(diff_point_id, player_stats_diffs)| {
-                            let (previous_diff_point_id, timestamp) =
-                                diff_point_data_map[&diff_point_id];
-
-                            DiffPoint {
-                                id: diff_point_id,
-                                previous_diff_point_id,
-                                diff: SnapshotDiff {
-                                    utc_timestamp: Utc.from_utc_datetime(&timestamp),
-                                    player_stats_diffs,
-                                },
-                            }
-                        })
-                        .collect(),
-                ))
+                Ok(assemble_diff_points(diff_point_data_map, diffs))
             }
 
             #[tracing::instrument(skip(conn))]
@@ -309,15 +479,43 @@ macro_rules! impl_has_incremental_snapshot_tables {
                     NewestBefore(timestamp) => Ok(dsl::$full_snapshot_point_table
                         .select((dsl::id, dsl::record_timestamp))
                         .filter(dsl::record_timestamp.le(timestamp.naive_utc()))
-                        .order(dsl::record_timestamp.desc())
+                        .order((dsl::record_timestamp.desc(), dsl::id.desc()))
                         .first_optional::<(u64, NaiveDateTime)>(conn)
                         .await?),
                     OldestAfter(timestamp) => Ok(dsl::$full_snapshot_point_table
                         .select((dsl::id, dsl::record_timestamp))
                         .filter(dsl::record_timestamp.ge(timestamp.naive_utc()))
-                        .order(dsl::record_timestamp.asc())
+                        .order((dsl::record_timestamp.asc(), dsl::id.asc()))
+                        .first_optional::<(u64, NaiveDateTime)>(conn)
+                        .await?),
+                    Nearest(timestamp) => {
+                        let newest_before =
+                            Self::find_id_and_timestamp_of_full_snapshot_point_with_condition(
+                                NewestBefore(timestamp),
+                                conn,
+                            )
+                            .await?;
+                        let oldest_after =
+                            Self::find_id_and_timestamp_of_full_snapshot_point_with_condition(
+                                OldestAfter(timestamp),
+                                conn,
+                            )
+                            .await?;
+
+                        Ok(closer_to(timestamp.naive_utc(), newest_before, oldest_after))
+                    }
+                    Exact(timestamp) => Ok(dsl::$full_snapshot_point_table
+                        .select((dsl::id, dsl::record_timestamp))
+                        .filter(dsl::record_timestamp.eq(timestamp.naive_utc()))
+                        .order(dsl::id.desc())
                         .first_optional::<(u64, NaiveDateTime)>(conn)
                         .await?),
+                    AtPoint(SnapshotPointId::Full(id)) => Ok(dsl::$full_snapshot_point_table
+                        .select((dsl::id, dsl::record_timestamp))
+                        .filter(dsl::id.eq(id))
+                        .first_optional::<(u64, NaiveDateTime)>(conn)
+                        .await?),
+                    AtPoint(SnapshotPointId::Diff(_)) => Ok(None),
                 }
             }
 
@@ -331,15 +529,43 @@ macro_rules! impl_has_incremental_snapshot_tables {
                     NewestBefore(timestamp) => Ok(dsl::$diff_point_table
                         .select((dsl::id, dsl::record_timestamp))
                         .filter(dsl::record_timestamp.le(timestamp.naive_utc()))
-                        .order(dsl::record_timestamp.desc())
+                        .order((dsl::record_timestamp.desc(), dsl::id.desc()))
                         .first_optional::<(DiffPointId, NaiveDateTime)>(conn)
                         .await?),
                     OldestAfter(timestamp) => Ok(dsl::$diff_point_table
                         .select((dsl::id, dsl::record_timestamp))
                         .filter(dsl::record_timestamp.ge(timestamp.naive_utc()))
-                        .order(dsl::record_timestamp.asc())
+                        .order((dsl::record_timestamp.asc(), dsl::id.asc()))
+                        .first_optional::<(DiffPointId, NaiveDateTime)>(conn)
+                        .await?),
+                    Nearest(timestamp) => {
+                        let newest_before =
+                            Self::find_id_and_timestamp_of_diff_snapshot_point_with_condition(
+                                NewestBefore(timestamp),
+                                conn,
+                            )
+                            .await?;
+                        let oldest_after =
+                            Self::find_id_and_timestamp_of_diff_snapshot_point_with_condition(
+                                OldestAfter(timestamp),
+                                conn,
+                            )
+                            .await?;
+
+                        Ok(closer_to(timestamp.naive_utc(), newest_before, oldest_after))
+                    }
+                    Exact(timestamp) => Ok(dsl::$diff_point_table
+                        .select((dsl::id, dsl::record_timestamp))
+                        .filter(dsl::record_timestamp.eq(timestamp.naive_utc()))
+                        .order(dsl::id.desc())
+                        .first_optional::<(DiffPointId, NaiveDateTime)>(conn)
+                        .await?),
+                    AtPoint(SnapshotPointId::Diff(id)) => Ok(dsl::$diff_point_table
+                        .select((dsl::id, dsl::record_timestamp))
+                        .filter(dsl::id.eq(DiffPointId(id)))
                         .first_optional::<(DiffPointId, NaiveDateTime)>(conn)
                         .await?),
+                    AtPoint(SnapshotPointId::Full(_)) => Ok(None),
                 }
             }
 
@@ -352,7 +578,7 @@ macro_rules! impl_has_incremental_snapshot_tables {
                 Ok(dsl::$full_snapshot_point_table
                     .select(dsl::id)
                     .filter(dsl::record_timestamp.le(timestamp.naive_utc()))
-                    .order(dsl::record_timestamp.desc())
+                    .order((dsl::record_timestamp.desc(), dsl::id.desc()))
                     .first_optional::<u64>(conn)
                     .await?)
             }
@@ -387,6 +613,258 @@ macro_rules! impl_has_incremental_snapshot_tables {
                     .collect();
                 Ok(diff_point_id_to_previous_diff_point_id)
             }
+
+            #[tracing::instrument(skip(conn))]
+            async fn read_header_of_diff_snapshot_point(
+                diff_point_id: DiffPointId,
+                conn: &mut Connection,
+            ) -> anyhow::Result<SnapshotPointHeader> {
+                use schema::$diff_point_table::dsl;
+                let (root_full_snapshot_point_id, previous_diff_point_id, record_timestamp) =
+                    dsl::$diff_point_table
+                        .select((
+                            dsl::root_full_snapshot_point_id,
+                            dsl::previous_diff_point_id,
+                            dsl::record_timestamp,
+                        ))
+                        .filter(dsl::id.eq(diff_point_id))
+                        .first::<(u64, Option<DiffPointId>, NaiveDateTime)>(conn)
+                        .await?;
+
+                Ok(SnapshotPointHeader {
+                    reference: SnapshotPointReference::Diff(diff_point_id),
+                    root_full_snapshot_point_id,
+                    previous_diff_point_id,
+                    utc_timestamp: Utc.from_utc_datetime(&record_timestamp),
+                })
+            }
+
+            #[tracing::instrument(skip(conn))]
+            async fn find_headers_of_full_snapshot_points_between(
+                from: DateTime<Utc>,
+                to: DateTime<Utc>,
+                conn: &mut Connection,
+            ) -> anyhow::Result<Vec<SnapshotPointHeader>> {
+                use schema::$full_snapshot_point_table::dsl;
+                Ok(dsl::$full_snapshot_point_table
+                    .select((dsl::id, dsl::record_timestamp))
+                    .filter(dsl::record_timestamp.between(from.naive_utc(), to.naive_utc()))
+                    .order((dsl::record_timestamp.asc(), dsl::id.asc()))
+                    .load::<(u64, NaiveDateTime)>(conn)
+                    .await?
+                    .into_iter()
+                    .map(|(id, record_timestamp)| SnapshotPointHeader {
+                        reference: SnapshotPointReference::Full(id),
+                        root_full_snapshot_point_id: id,
+                        previous_diff_point_id: None,
+                        utc_timestamp: Utc.from_utc_datetime(&record_timestamp),
+                    })
+                    .collect())
+            }
+
+            #[tracing::instrument(skip(conn))]
+            async fn find_headers_of_diff_snapshot_points_between(
+                from: DateTime<Utc>,
+                to: DateTime<Utc>,
+                conn: &mut Connection,
+            ) -> anyhow::Result<Vec<SnapshotPointHeader>> {
+                use schema::$diff_point_table::dsl;
+                Ok(dsl::$diff_point_table
+                    .select((
+                        dsl::id,
+                        dsl::root_full_snapshot_point_id,
+                        dsl::previous_diff_point_id,
+                        dsl::record_timestamp,
+                    ))
+                    .filter(dsl::record_timestamp.between(from.naive_utc(), to.naive_utc()))
+                    .order((dsl::record_timestamp.asc(), dsl::id.asc()))
+                    .load::<(DiffPointId, u64, Option<DiffPointId>, NaiveDateTime)>(conn)
+                    .await?
+                    .into_iter()
+                    .map(
+                        |(id, root_full_snapshot_point_id, previous_diff_point_id, record_timestamp)| {
+                            SnapshotPointHeader {
+                                reference: SnapshotPointReference::Diff(id),
+                                root_full_snapshot_point_id,
+                                previous_diff_point_id,
+                                utc_timestamp: Utc.from_utc_datetime(&record_timestamp),
+                            }
+                        },
+                    )
+                    .collect())
+            }
+
+            #[tracing::instrument(skip(conn))]
+            async fn find_id_of_last_full_snapshot_point(
+                conn: &mut Connection,
+            ) -> anyhow::Result<Option<u64>> {
+                use schema::$full_snapshot_point_table::dsl;
+                Ok(dsl::$full_snapshot_point_table
+                    .select(dsl::id)
+                    .order(dsl::id.desc())
+                    .first_optional::<u64>(conn)
+                    .await?)
+            }
+
+            #[tracing::instrument(skip(conn))]
+            async fn find_id_of_last_diff_snapshot_point(
+                conn: &mut Connection,
+            ) -> anyhow::Result<Option<DiffPointId>> {
+                use schema::$diff_point_table::dsl;
+                Ok(dsl::$diff_point_table
+                    .select(dsl::id)
+                    .order(dsl::id.desc())
+                    .first_optional::<DiffPointId>(conn)
+                    .await?)
+            }
+
+            #[tracing::instrument(skip(conn))]
+            async fn find_headers_of_full_snapshot_points_with_id_greater_than(
+                last_id: Option<u64>,
+                conn: &mut Connection,
+            ) -> anyhow::Result<Vec<SnapshotPointHeader>> {
+                use schema::$full_snapshot_point_table::dsl;
+                Ok(dsl::$full_snapshot_point_table
+                    .select((dsl::id, dsl::record_timestamp))
+                    // AUTO_INCREMENT による ID は 1 から始まるため、 0 より大きな ID はすべてのデータ点を指す
+                    .filter(dsl::id.gt(last_id.unwrap_or(0)))
+                    .order(dsl::id.asc())
+                    .load::<(u64, NaiveDateTime)>(conn)
+                    .await?
+                    .into_iter()
+                    .map(|(id, record_timestamp)| SnapshotPointHeader {
+                        reference: SnapshotPointReference::Full(id),
+                        root_full_snapshot_point_id: id,
+                        previous_diff_point_id: None,
+                        utc_timestamp: Utc.from_utc_datetime(&record_timestamp),
+                    })
+                    .collect())
+            }
+
+            #[tracing::instrument(skip(conn))]
+            async fn find_headers_of_diff_snapshot_points_with_id_greater_than(
+                last_id: Option<DiffPointId>,
+                conn: &mut Connection,
+            ) -> anyhow::Result<Vec<SnapshotPointHeader>> {
+                use schema::$diff_point_table::dsl;
+                Ok(dsl::$diff_point_table
+                    .select((
+                        dsl::id,
+                        dsl::root_full_snapshot_point_id,
+                        dsl::previous_diff_point_id,
+                        dsl::record_timestamp,
+                    ))
+                    // AUTO_INCREMENT による ID は 1 から始まるため、 0 より大きな ID はすべてのデータ点を指す
+                    .filter(dsl::id.gt(last_id.unwrap_or(DiffPointId(0))))
+                    .order(dsl::id.asc())
+                    .load::<(DiffPointId, u64, Option<DiffPointId>, NaiveDateTime)>(conn)
+                    .await?
+                    .into_iter()
+                    .map(
+                        |(id, root_full_snapshot_point_id, previous_diff_point_id, record_timestamp)| {
+                            SnapshotPointHeader {
+                                reference: SnapshotPointReference::Diff(id),
+                                root_full_snapshot_point_id,
+                                previous_diff_point_id,
+                                utc_timestamp: Utc.from_utc_datetime(&record_timestamp),
+                            }
+                        },
+                    )
+                    .collect())
+            }
+
+            #[tracing::instrument(skip(players, full_snapshot_point_ids, conn))]
+            async fn read_stats_of_players_at_full_snapshot_points(
+                players: &HashSet<PlayerUuidString>,
+                full_snapshot_point_ids: HashSet<u64>,
+                conn: &mut Connection,
+            ) -> anyhow::Result<HashMap<u64, HashMap<PlayerUuidString, Self>>> {
+                use schema::$full_snapshot_dsl_table::dsl;
+                let player_uuids = players
+                    .iter()
+                    .map(|player_uuid| player_uuid.as_str())
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                let records = dsl::$full_snapshot_dsl_table
+                    .inner_join(schema::player::table)
+                    .select((dsl::full_snapshot_point_id, schema::player::uuid, dsl::value))
+                    .filter(dsl::full_snapshot_point_id.eq_any(&full_snapshot_point_ids))
+                    .filter(schema::player::uuid.eq_any(player_uuids))
+                    .load::<(u64, String, u64)>(conn)
+                    .await?;
+
+                let mut stats_at_points = HashMap::new();
+                for (point_id, uuid, value) in records {
+                    stats_at_points
+                        .entry(point_id)
+                        .or_insert_with(|| HashMap::new())
+                        .insert(
+                            PlayerUuidString::from_string(&uuid)?,
+                            Self::from_value_column(value),
+                        );
+                }
+
+                Ok(stats_at_points)
+            }
+
+            #[tracing::instrument(
+                skip(players, diff_snapshot_point_ids, conn),
+                fields(diff_point_ids = display_diff_point_ids_for_tracing(&diff_snapshot_point_ids))
+            )]
+            async fn read_stats_of_players_at_diff_snapshot_points(
+                players: &HashSet<PlayerUuidString>,
+                diff_snapshot_point_ids: HashSet<DiffPointId>,
+                conn: &mut Connection,
+            ) -> anyhow::Result<HashMap<DiffPointId, HashMap<PlayerUuidString, Option<Self>>>> {
+                use schema::$diff_table::dsl;
+                let player_uuids = players
+                    .iter()
+                    .map(|player_uuid| player_uuid.as_str())
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                let records = dsl::$diff_table
+                    .inner_join(schema::player::table)
+                    .select((dsl::diff_point_id, schema::player::uuid, dsl::new_value))
+                    .filter(dsl::diff_point_id.eq_any(&diff_snapshot_point_ids))
+                    .filter(schema::player::uuid.eq_any(player_uuids))
+                    .load::<(DiffPointId, String, Option<u64>)>(conn)
+                    .await?;
+
+                let mut stats_at_points = HashMap::new();
+                for (point_id, uuid, new_value) in records {
+                    stats_at_points
+                        .entry(point_id)
+                        .or_insert_with(|| HashMap::new())
+                        .insert(
+                            PlayerUuidString::from_string(&uuid)?,
+                            new_value.map(Self::from_value_column),
+                        );
+                }
+
+                Ok(stats_at_points)
+            }
+
+            #[tracing::instrument(
+                skip(diff_snapshot_point_ids, conn),
+                fields(diff_point_ids = display_diff_point_ids_for_tracing(&diff_snapshot_point_ids))
+            )]
+            async fn count_records_at_diff_snapshot_points(
+                diff_snapshot_point_ids: HashSet<DiffPointId>,
+                conn: &mut Connection,
+            ) -> anyhow::Result<HashMap<DiffPointId, u64>> {
+                use schema::$diff_table::dsl;
+                dsl::$diff_table
+                    .filter(dsl::diff_point_id.eq_any(&diff_snapshot_point_ids))
+                    .group_by(dsl::diff_point_id)
+                    .select((dsl::diff_point_id, diesel::dsl::count_star()))
+                    .load::<(DiffPointId, i64)>(conn)
+                    .await?
+                    .into_iter()
+                    .map(|(diff_point_id, count)| {
+                        anyhow::Ok((diff_point_id, u64::try_from(count)?))
+                    })
+                    .collect()
+            }
         }
     };
 }
@@ -422,3 +900,35 @@ impl_has_incremental_snapshot_tables!(
     vote_count_diff_point,
     vote_count_diff
 );
+
+#[cfg(test)]
+mod tests {
+    use domain::test_fixtures::{player, timestamp};
+
+    use super::*;
+
+    #[test]
+    fn assemble_diff_points_keeps_points_without_diff_records() {
+        let diff_point_data_map = HashMap::from([
+            (DiffPointId(1), (None, timestamp(0).naive_utc())),
+            (
+                DiffPointId(2),
+                (Some(DiffPointId(1)), timestamp(1).naive_utc()),
+            ),
+        ]);
+        let diffs = vec![(DiffPointId(1), player(1).uuid, Some(BreakCount(10)))];
+
+        let mut diff_points = assemble_diff_points(diff_point_data_map, diffs);
+
+        let empty_point = diff_points.remove(&DiffPointId(2)).unwrap();
+        assert_eq!(empty_point.previous_diff_point_id, Some(DiffPointId(1)));
+        assert_eq!(empty_point.diff.utc_timestamp, timestamp(1));
+        assert!(empty_point.diff.player_stats_diffs.is_empty());
+
+        let point = diff_points.remove(&DiffPointId(1)).unwrap();
+        assert_eq!(
+            point.diff.player_stats_diffs,
+            HashMap::from([(player(1).uuid, Some(BreakCount(10)))])
+        );
+    }
+}
@@ -1,10 +1,10 @@
 // @generated automatically by Diesel CLI.
 
 diesel::table! {
-    break_count_diff (diff_point_id, player_uuid) {
+    break_count_diff (diff_point_id, player_id) {
         diff_point_id -> Unsigned<Bigint>,
-        player_uuid -> Char,
-        new_value -> Unsigned<Bigint>,
+        player_id -> Unsigned<Integer>,
+        new_value -> Nullable<Unsigned<Bigint>>,
     }
 }
 
@@ -18,9 +18,9 @@ diesel::table! {
 }
 
 diesel::table! {
-    break_count_full_snapshot (full_snapshot_point_id, player_uuid) {
+    break_count_full_snapshot (full_snapshot_point_id, player_id) {
         full_snapshot_point_id -> Unsigned<Bigint>,
-        player_uuid -> Char,
+        player_id -> Unsigned<Integer>,
         value -> Unsigned<Bigint>,
     }
 }
@@ -33,10 +33,10 @@ diesel::table! {
 }
 
 diesel::table! {
-    build_count_diff (diff_point_id, player_uuid) {
+    build_count_diff (diff_point_id, player_id) {
         diff_point_id -> Unsigned<Bigint>,
-        player_uuid -> Char,
-        new_value -> Unsigned<Bigint>,
+        player_id -> Unsigned<Integer>,
+        new_value -> Nullable<Unsigned<Bigint>>,
     }
 }
 
@@ -50,9 +50,9 @@ diesel::table! {
 }
 
 diesel::table! {
-    build_count_full_snapshot (full_snapshot_point_id, player_uuid) {
+    build_count_full_snapshot (full_snapshot_point_id, player_id) {
         full_snapshot_point_id -> Unsigned<Bigint>,
-        player_uuid -> Char,
+        player_id -> Unsigned<Integer>,
         value -> Unsigned<Bigint>,
     }
 }
@@ -65,10 +65,10 @@ diesel::table! {
 }
 
 diesel::table! {
-    play_ticks_diff (diff_point_id, player_uuid) {
+    play_ticks_diff (diff_point_id, player_id) {
         diff_point_id -> Unsigned<Bigint>,
-        player_uuid -> Char,
-        new_value -> Unsigned<Bigint>,
+        player_id -> Unsigned<Integer>,
+        new_value -> Nullable<Unsigned<Bigint>>,
     }
 }
 
@@ -82,9 +82,9 @@ diesel::table! {
 }
 
 diesel::table! {
-    play_ticks_full_snapshot (full_snapshot_point_id, player_uuid) {
+    play_ticks_full_snapshot (full_snapshot_point_id, player_id) {
         full_snapshot_point_id -> Unsigned<Bigint>,
-        player_uuid -> Char,
+        player_id -> Unsigned<Integer>,
         value -> Unsigned<Bigint>,
     }
 }
@@ -97,10 +97,17 @@ diesel::table! {
 }
 
 diesel::table! {
-    vote_count_diff (diff_point_id, player_uuid) {
+    player (id) {
+        id -> Unsigned<Integer>,
+        uuid -> Char,
+    }
+}
+
+diesel::table! {
+    vote_count_diff (diff_point_id, player_id) {
         diff_point_id -> Unsigned<Bigint>,
-        player_uuid -> Char,
-        new_value -> Unsigned<Bigint>,
+        player_id -> Unsigned<Integer>,
+        new_value -> Nullable<Unsigned<Bigint>>,
     }
 }
 
@@ -114,9 +121,9 @@ diesel::table! {
 }
 
 diesel::table! {
-    vote_count_full_snapshot (full_snapshot_point_id, player_uuid) {
+    vote_count_full_snapshot (full_snapshot_point_id, player_id) {
         full_snapshot_point_id -> Unsigned<Bigint>,
-        player_uuid -> Char,
+        player_id -> Unsigned<Integer>,
         value -> Unsigned<Bigint>,
     }
 }
@@ -129,17 +136,25 @@ diesel::table! {
 }
 
 diesel::joinable!(break_count_diff -> break_count_diff_point (diff_point_id));
+diesel::joinable!(break_count_diff -> player (player_id));
 diesel::joinable!(break_count_diff_point -> break_count_full_snapshot_point (root_full_snapshot_point_id));
 diesel::joinable!(break_count_full_snapshot -> break_count_full_snapshot_point (full_snapshot_point_id));
+diesel::joinable!(break_count_full_snapshot -> player (player_id));
 diesel::joinable!(build_count_diff -> build_count_diff_point (diff_point_id));
+diesel::joinable!(build_count_diff -> player (player_id));
 diesel::joinable!(build_count_diff_point -> build_count_full_snapshot_point (root_full_snapshot_point_id));
 diesel::joinable!(build_count_full_snapshot -> build_count_full_snapshot_point (full_snapshot_point_id));
+diesel::joinable!(build_count_full_snapshot -> player (player_id));
 diesel::joinable!(play_ticks_diff -> play_ticks_diff_point (diff_point_id));
+diesel::joinable!(play_ticks_diff -> player (player_id));
 diesel::joinable!(play_ticks_diff_point -> play_ticks_full_snapshot_point (root_full_snapshot_point_id));
 diesel::joinable!(play_ticks_full_snapshot -> play_ticks_full_snapshot_point (full_snapshot_point_id));
+diesel::joinable!(play_ticks_full_snapshot -> player (player_id));
 diesel::joinable!(vote_count_diff -> vote_count_diff_point (diff_point_id));
+diesel::joinable!(vote_count_diff -> player (player_id));
 diesel::joinable!(vote_count_diff_point -> vote_count_full_snapshot_point (root_full_snapshot_point_id));
 diesel::joinable!(vote_count_full_snapshot -> vote_count_full_snapshot_point (full_snapshot_point_id));
+diesel::joinable!(vote_count_full_snapshot -> player (player_id));
 
 diesel::allow_tables_to_appear_in_same_query!(
     break_count_diff,
@@ -154,6 +169,7 @@ diesel::allow_tables_to_appear_in_same_query!(
     play_ticks_diff_point,
     play_ticks_full_snapshot,
     play_ticks_full_snapshot_point,
+    player,
     vote_count_diff,
     vote_count_diff_point,
     vote_count_full_snapshot,
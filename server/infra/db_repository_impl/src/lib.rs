@@ -1,15 +1,21 @@
+use chrono::{DateTime, SubsecRound, TimeZone, Utc};
 use diesel::sql_query;
 use diesel_async::pooled_connection::deadpool::{Object, Pool};
 use diesel_async::pooled_connection::AsyncDieselConnectionManager;
 use diesel_async::{scoped_futures::ScopedFutureExt, RunQueryDsl};
 use diesel_async::{AsyncConnection, AsyncMysqlConnection};
-use domain::repositories::TimeBasedSnapshotSearchCondition;
+use domain::models::{
+    AllStatsSnapshot, BreakCount, BuildCount, LastSnapshotPointIds, PlayTicks, Player,
+    ResampledStats, SnapshotPointId, SnapshotPointMetadata, StatsSnapshotChunks, TimeSeriesPoints,
+    TimeSeriesSampling, TimestampedRank, TimestampedStats, VoteCount,
+};
+use domain::repositories::{PlayerAllTimedStatsRepository, TimeBasedSnapshotSearchCondition};
 use domain::{models::StatsSnapshot, repositories::PlayerTimedStatsRepository};
+use std::collections::{HashMap, HashSet};
 use std::fmt::Debug;
+use std::sync::Arc;
 
-use crate::structures_embedded_in_rdb::{
-    choose_base_diff_sequence_for_snapshot_with_heuristics, DiffSequence, DiffSequenceChoice,
-};
+use crate::snapshot_cache::{unwrap_or_clone, ReconstructedSnapshotCache};
 use stats_with_incremental_snapshot_tables::{
     HasIncrementalSnapshotTables, HasIncrementalSnapshotTablesDefaultMethods,
 };
@@ -17,6 +23,7 @@ use stats_with_incremental_snapshot_tables::{
 mod cycle_free_path;
 mod debugging_utils;
 mod diesel_based_impl;
+mod snapshot_cache;
 mod stats_with_incremental_snapshot_tables;
 mod structures_embedded_in_rdb;
 
@@ -28,6 +35,13 @@ pub mod config {
         pub db_connection_password: String,
         pub db_connection_database: String,
         pub db_connection_pool_size: usize,
+        /// 復元済みのスナップショットをキャッシュしておく最大の個数。 0 の場合はキャッシュしない。
+        #[serde(default = "default_snapshot_cache_capacity")]
+        pub db_snapshot_cache_capacity: usize,
+    }
+
+    const fn default_snapshot_cache_capacity() -> usize {
+        8
     }
 
     impl Database {
@@ -39,6 +53,90 @@ pub mod config {
 
 pub struct DatabaseConnector {
     pool: Pool<AsyncMysqlConnection>,
+    snapshot_cache: ReconstructedSnapshotCache,
+}
+
+/// データベースが利用可能かどうかを調べるためのもの。 `DatabaseConnector` とコネクションプールを共有する。
+#[derive(Clone)]
+pub struct DatabaseAvailabilityProbe {
+    pool: Pool<AsyncMysqlConnection>,
+}
+
+impl DatabaseAvailabilityProbe {
+    /// コネクションプールから接続を取得し、その接続でクエリを実行できることを確かめる。
+    #[tracing::instrument(skip(self))]
+    pub async fn check(&self) -> anyhow::Result<()> {
+        let mut conn = self.pool.get().await?;
+        sql_query("SELECT 1").execute(&mut conn).await?;
+
+        Ok(())
+    }
+}
+
+/// スナップショットをデータ点の森に書き込む方法。
+#[derive(Debug, Clone, Copy)]
+enum SnapshotWriteMode {
+    /// 最新の統計量として記録する。
+    /// スナップショットの時刻以降に記録されたデータ点が既に存在する場合はエラーとする。
+    Record,
+    /// 過去の時刻の統計量として後から記録する。
+    /// スナップショットの時刻ちょうどに記録されたデータ点が既に存在する場合はエラーとする。
+    Backfill,
+}
+
+impl SnapshotWriteMode {
+    /// `timestamp` の時刻のスナップショットをこの方法で書き込む際に、
+    /// 存在してはならないデータ点を探すための条件。
+    const fn conflicting_point_condition(
+        self,
+        timestamp: DateTime<Utc>,
+    ) -> TimeBasedSnapshotSearchCondition {
+        match self {
+            Self::Record => TimeBasedSnapshotSearchCondition::OldestAfter(timestamp),
+            Self::Backfill => TimeBasedSnapshotSearchCondition::Exact(timestamp),
+        }
+    }
+}
+
+/// 書き込み用のロックを取得するための接続。
+///
+/// `release` によってロックを解放しないまま破棄された場合 (書き込みがエラーで中断された場合や、
+/// future が破棄されたりパニックしたりした場合) は、接続をコネクションプールに戻さずに閉じる。
+/// MySQL は名前付きロックをセッションの終了時に解放するため、ロックを保持したままの接続がプールに残ることはない。
+struct WriterLockConnection {
+    conn: Option<Object<AsyncMysqlConnection>>,
+}
+
+impl WriterLockConnection {
+    fn new(conn: Object<AsyncMysqlConnection>) -> Self {
+        Self { conn: Some(conn) }
+    }
+
+    fn conn(&mut self) -> &mut Object<AsyncMysqlConnection> {
+        self.conn
+            .as_mut()
+            .expect("connection is taken only when the lock is released")
+    }
+
+    /// ロックを解放し、接続をコネクションプールに戻す。
+    /// 解放に失敗した場合は、書き込みの結果を優先するためにエラーを記録するのみとし、接続は閉じる。
+    async fn release<Stats>(mut self)
+    where
+        Stats: HasIncrementalSnapshotTables<Object<AsyncMysqlConnection>>,
+    {
+        match Stats::release_writer_lock(self.conn()).await {
+            Ok(()) => drop(self.conn.take()),
+            Err(error) => tracing::error!("failed to release writer lock: {error:?}"),
+        }
+    }
+}
+
+impl Drop for WriterLockConnection {
+    fn drop(&mut self) {
+        if let Some(conn) = self.conn.take() {
+            drop(Object::take(conn));
+        }
+    }
 }
 
 impl DatabaseConnector {
@@ -56,49 +154,180 @@ impl DatabaseConnector {
             .max_size(config.db_connection_pool_size)
             .build()?;
 
-        Ok(Self { pool })
+        Ok(Self {
+            pool,
+            snapshot_cache: ReconstructedSnapshotCache::new(config.db_snapshot_cache_capacity),
+        })
+    }
+
+    pub fn availability_probe(&self) -> DatabaseAvailabilityProbe {
+        DatabaseAvailabilityProbe {
+            pool: self.pool.clone(),
+        }
+    }
+
+    async fn reconstruct_snapshot_with_condition<Stats>(
+        &self,
+        condition: TimeBasedSnapshotSearchCondition,
+    ) -> anyhow::Result<Option<Arc<StatsSnapshot<Stats>>>>
+    where
+        Stats: Debug
+            + HasIncrementalSnapshotTables<Object<AsyncMysqlConnection>>
+            + Send
+            + Sync
+            + 'static,
+    {
+        let mut conn = self.pool.get().await?;
+        let cache = &self.snapshot_cache;
+        conn.transaction(|conn| {
+            async move { Stats::reconstruct_snapshot_with_condition(condition, cache, conn).await }
+                .scope_boxed()
+        })
+        .await
+    }
+
+    async fn write_snapshot<Stats>(
+        &self,
+        snapshot: StatsSnapshot<Stats>,
+        mode: SnapshotWriteMode,
+    ) -> anyhow::Result<()>
+    where
+        Stats: Debug + HasIncrementalSnapshotTables<Object<AsyncMysqlConnection>> + Send + 'static,
+    {
+        // データ点の時刻はマイクロ秒精度で記録されるため、比較に用いる時刻も予め切り捨てておく
+        let snapshot = StatsSnapshot {
+            utc_timestamp: snapshot.utc_timestamp.trunc_subsecs(6),
+            ..snapshot
+        };
+
+        let mut lock_connection = WriterLockConnection::new(self.pool.get().await?);
+
+        // 複数のプロセスが同時に書き込むと、同じデータ点を基底とする差分を互いに知らずに作ってしまうため、
+        // 書き込みの間は統計量ごとのロックを保持する
+        Stats::acquire_writer_lock(lock_connection.conn()).await?;
+
+        let conn = lock_connection.conn();
+        sql_query("SET TRANSACTION ISOLATION LEVEL SERIALIZABLE")
+            .execute(conn)
+            .await?;
+
+        conn.transaction(|conn| {
+            async move {
+                let conflicting_point = Stats::locate_snapshot_point_with_condition(
+                    mode.conflicting_point_condition(snapshot.utc_timestamp),
+                    conn,
+                )
+                .await?;
+                if let Some((_, conflicting_timestamp)) = conflicting_point {
+                    anyhow::bail!(
+                        "a snapshot point at {} has already been recorded, so a snapshot at {} cannot be written in {:?} mode",
+                        Utc.from_utc_datetime(&conflicting_timestamp),
+                        snapshot.utc_timestamp,
+                        mode
+                    );
+                }
+
+                Stats::insert_snapshot_into_forest(snapshot, conn).await
+            }
+            .scope_boxed()
+        })
+        .await?;
+
+        lock_connection.release::<Stats>().await;
+
+        Ok(())
+    }
+
+    async fn reconstruct_snapshot_chunks_with_condition<Stats>(
+        &self,
+        condition: TimeBasedSnapshotSearchCondition,
+        chunk_size: usize,
+    ) -> anyhow::Result<Option<StatsSnapshotChunks<Stats>>>
+    where
+        Stats: Debug
+            + HasIncrementalSnapshotTables<Object<AsyncMysqlConnection>>
+            + Send
+            + Sync
+            + 'static,
+    {
+        let mut conn = self.pool.get().await?;
+        let cache = &self.snapshot_cache;
+        conn.transaction(|conn| {
+            async move {
+                Stats::reconstruct_snapshot_chunks_with_condition(
+                    condition, chunk_size, cache, conn,
+                )
+                .await
+            }
+            .scope_boxed()
+        })
+        .await
     }
 }
 
 #[async_trait::async_trait]
 impl<
-        Stats: Debug + HasIncrementalSnapshotTables<Object<AsyncMysqlConnection>> + Send + 'static,
+        Stats: Debug
+            + Ord
+            + HasIncrementalSnapshotTables<Object<AsyncMysqlConnection>>
+            + Send
+            + Sync
+            + 'static,
     > PlayerTimedStatsRepository<Stats> for DatabaseConnector
 {
     #[tracing::instrument(skip(self), fields(stats_type = std::any::type_name::<Stats>()))]
     async fn record_snapshot(&self, snapshot: StatsSnapshot<Stats>) -> anyhow::Result<()> {
-        let mut conn = self.pool.get().await?;
-        sql_query("SET TRANSACTION ISOLATION LEVEL SERIALIZABLE")
-            .execute(&mut conn)
+        self.write_snapshot(snapshot, SnapshotWriteMode::Record)
+            .await
+    }
+
+    #[tracing::instrument(skip(self), fields(stats_type = std::any::type_name::<Stats>()))]
+    async fn backfill_snapshot(&self, snapshot: StatsSnapshot<Stats>) -> anyhow::Result<()> {
+        self.write_snapshot(snapshot, SnapshotWriteMode::Backfill)
+            .await
+    }
+
+    #[tracing::instrument(skip(self), fields(stats_type = std::any::type_name::<Stats>()))]
+    async fn search_snapshot(
+        &self,
+        condition: TimeBasedSnapshotSearchCondition,
+    ) -> anyhow::Result<Option<StatsSnapshot<Stats>>> {
+        let snapshot = self
+            .reconstruct_snapshot_with_condition::<Stats>(condition)
             .await?;
 
+        Ok(snapshot.map(unwrap_or_clone))
+    }
+
+    #[tracing::instrument(skip(self), fields(stats_type = std::any::type_name::<Stats>()))]
+    async fn search_snapshot_in_chunks(
+        &self,
+        condition: TimeBasedSnapshotSearchCondition,
+        chunk_size: usize,
+    ) -> anyhow::Result<Option<StatsSnapshotChunks<Stats>>> {
+        self.reconstruct_snapshot_chunks_with_condition::<Stats>(condition, chunk_size)
+            .await
+    }
+
+    #[tracing::instrument(
+        skip(self, players),
+        fields(stats_type = std::any::type_name::<Stats>(), player_count = players.len())
+    )]
+    async fn search_snapshot_of_players(
+        &self,
+        players: HashSet<Player>,
+        condition: TimeBasedSnapshotSearchCondition,
+    ) -> anyhow::Result<Option<StatsSnapshot<Stats>>> {
+        let players = players
+            .into_iter()
+            .map(|player| player.uuid)
+            .collect::<HashSet<_>>();
+        let players = &players;
+
+        let mut conn = self.pool.get().await?;
         conn.transaction(|conn| {
             async move {
-                if let Some(full_snapshot) =
-                    Stats::find_latest_full_snapshot_before(snapshot.utc_timestamp, conn).await?
-                {
-                    let diff_points_over_full_snapshot =
-                        Stats::read_diff_snapshot_points_over_full_point(full_snapshot.id, conn)
-                            .await?
-                            .points_before(snapshot.utc_timestamp);
-
-                    let diff_sequence_choice =
-                        choose_base_diff_sequence_for_snapshot_with_heuristics(
-                            full_snapshot,
-                            diff_points_over_full_snapshot,
-                            &snapshot,
-                        )?;
-
-                    if let DiffSequenceChoice::OptimalAccordingToHeuristics(diff_sequence) =
-                        diff_sequence_choice
-                    {
-                        Stats::create_diff_snapshot_point_on(diff_sequence, snapshot, conn).await
-                    } else {
-                        Stats::create_full_snapshot(snapshot, conn).await
-                    }
-                } else {
-                    Stats::create_full_snapshot(snapshot, conn).await
-                }
+                Stats::read_snapshot_of_players_with_condition(players, condition, conn).await
             }
             .scope_boxed()
         })
@@ -106,31 +335,167 @@ impl<
     }
 
     #[tracing::instrument(skip(self), fields(stats_type = std::any::type_name::<Stats>()))]
-    async fn search_snapshot(
+    async fn search_player_stats(
         &self,
+        player: Player,
         condition: TimeBasedSnapshotSearchCondition,
-    ) -> anyhow::Result<Option<StatsSnapshot<Stats>>> {
+    ) -> anyhow::Result<Option<TimestampedStats<Stats>>> {
+        let mut conn = self.pool.get().await?;
+        conn.transaction(|conn| {
+            async move {
+                Stats::read_stats_of_player_with_condition(player.uuid, condition, conn).await
+            }
+            .scope_boxed()
+        })
+        .await
+    }
+
+    #[tracing::instrument(skip(self), fields(stats_type = std::any::type_name::<Stats>()))]
+    async fn search_player_stats_history(
+        &self,
+        player: Player,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> anyhow::Result<Vec<TimestampedStats<Stats>>> {
+        let mut conn = self.pool.get().await?;
+        conn.transaction(|conn| {
+            async move { Stats::read_stats_history_of_player(player.uuid, from, to, conn).await }
+                .scope_boxed()
+        })
+        .await
+    }
+
+    #[tracing::instrument(skip(self), fields(stats_type = std::any::type_name::<Stats>()))]
+    async fn search_resampled_player_stats(
+        &self,
+        players: HashSet<Player>,
+        sampling: TimeSeriesSampling,
+    ) -> anyhow::Result<Vec<ResampledStats<Stats>>> {
+        let players = players
+            .into_iter()
+            .map(|player| player.uuid)
+            .collect::<HashSet<_>>();
+        let players = &players;
+
+        let mut conn = self.pool.get().await?;
+        conn.transaction(|conn| {
+            async move { Stats::read_resampled_stats_of_players(players, sampling, conn).await }
+                .scope_boxed()
+        })
+        .await
+    }
+
+    #[tracing::instrument(skip(self), fields(stats_type = std::any::type_name::<Stats>()))]
+    async fn search_player_rank_history(
+        &self,
+        player: Player,
+        points: TimeSeriesPoints,
+    ) -> anyhow::Result<Vec<TimestampedRank>> {
+        let cache = &self.snapshot_cache;
+
+        let mut conn = self.pool.get().await?;
+        conn.transaction(|conn| {
+            async move { Stats::read_rank_history_of_player(player.uuid, points, cache, conn).await }
+                .scope_boxed()
+        })
+        .await
+    }
+
+    #[tracing::instrument(skip(self), fields(stats_type = std::any::type_name::<Stats>()))]
+    async fn list_snapshot_points(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> anyhow::Result<Vec<SnapshotPointMetadata>> {
         let mut conn = self.pool.get().await?;
-        let diff_sequence_upto_latest_snapshot = conn
+        conn.transaction(|conn| {
+            async move { Stats::list_snapshot_points_between(from, to, conn).await }.scope_boxed()
+        })
+        .await
+    }
+
+    #[tracing::instrument(skip(self), fields(stats_type = std::any::type_name::<Stats>()))]
+    async fn last_snapshot_point_ids(&self) -> anyhow::Result<LastSnapshotPointIds> {
+        let mut conn = self.pool.get().await?;
+        conn.transaction(|conn| {
+            async move { Stats::find_last_snapshot_point_ids(conn).await }.scope_boxed()
+        })
+        .await
+    }
+
+    #[tracing::instrument(skip(self), fields(stats_type = std::any::type_name::<Stats>()))]
+    async fn list_snapshot_points_recorded_after(
+        &self,
+        last_ids: LastSnapshotPointIds,
+    ) -> anyhow::Result<Vec<SnapshotPointMetadata>> {
+        let mut conn = self.pool.get().await?;
+        conn.transaction(|conn| {
+            async move { Stats::list_snapshot_points_recorded_after(last_ids, conn).await }
+                .scope_boxed()
+        })
+        .await
+    }
+
+    #[tracing::instrument(skip(self), fields(stats_type = std::any::type_name::<Stats>()))]
+    async fn search_records_at_snapshot_point(
+        &self,
+        point: SnapshotPointId,
+    ) -> anyhow::Result<HashMap<Player, Option<Stats>>> {
+        let mut conn = self.pool.get().await?;
+        let records = conn
+            .transaction(|conn| {
+                async move { Stats::read_records_at_snapshot_point(point, conn).await }
+                    .scope_boxed()
+            })
+            .await?;
+
+        Ok(records
+            .into_iter()
+            .map(|(uuid, stats)| (Player { uuid }, stats))
+            .collect())
+    }
+}
+
+#[async_trait::async_trait]
+impl PlayerAllTimedStatsRepository for DatabaseConnector {
+    #[tracing::instrument(skip(self))]
+    async fn search_all_stats_snapshot(
+        &self,
+        condition: TimeBasedSnapshotSearchCondition,
+    ) -> anyhow::Result<AllStatsSnapshot> {
+        let mut conn = self.pool.get().await?;
+
+        let cache = &self.snapshot_cache;
+
+        // すべての統計量を同一のトランザクション内で読み出すことで、
+        // 統計量の間で一貫したスナップショットを得る
+        let (break_count, build_count, play_ticks, vote_count) = conn
             .transaction(|conn| {
                 async move {
-                    let snapshot_point =
-                        Stats::find_snapshot_point_with_condition(condition, conn).await?;
-
-                    if let Some(snapshot_point) = snapshot_point {
-                        let sequence =
-                            Stats::construct_diff_sequence_leading_up_to(snapshot_point, conn)
-                                .await?;
-
-                        anyhow::Ok(Some(sequence))
-                    } else {
-                        anyhow::Ok(None)
-                    }
+                    let break_count =
+                        BreakCount::reconstruct_snapshot_with_condition(condition, cache, conn)
+                            .await?;
+                    let build_count =
+                        BuildCount::reconstruct_snapshot_with_condition(condition, cache, conn)
+                            .await?;
+                    let play_ticks =
+                        PlayTicks::reconstruct_snapshot_with_condition(condition, cache, conn)
+                            .await?;
+                    let vote_count =
+                        VoteCount::reconstruct_snapshot_with_condition(condition, cache, conn)
+                            .await?;
+
+                    anyhow::Ok((break_count, build_count, play_ticks, vote_count))
                 }
                 .scope_boxed()
             })
             .await?;
 
-        Ok(diff_sequence_upto_latest_snapshot.map(DiffSequence::into_snapshot_at_the_tip))
+        Ok(AllStatsSnapshot::join(
+            break_count.map(unwrap_or_clone),
+            build_count.map(unwrap_or_clone),
+            play_ticks.map(unwrap_or_clone),
+            vote_count.map(unwrap_or_clone),
+        ))
     }
 }
@@ -0,0 +1,166 @@
+use std::any::{Any, TypeId};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+
+use domain::models::StatsSnapshot;
+
+use crate::structures_embedded_in_rdb::SnapshotPointReference;
+
+/// `Arc` が唯一の参照であれば中身を取り出し、そうでなければ中身を複製する。
+///
+/// キャッシュから得たスナップショットは内部では `Arc` のまま扱い、
+/// 所有権が必要になる箇所 (リポジトリのトレイトの境界や、差分を適用する箇所) でのみこれを用いて複製する。
+pub fn unwrap_or_clone<T: Clone>(arc: Arc<T>) -> T {
+    Arc::try_unwrap(arc).unwrap_or_else(|arc| (*arc).clone())
+}
+
+/// 統計量の型とデータ点の組。
+type CacheKey = (TypeId, SnapshotPointReference);
+
+/// 復元済みのスナップショットを、データ点ごとに高々 `capacity` 個まで保持するキャッシュ。
+///
+/// 一度記録されたデータ点の統計量が書き換えられることはないため、エントリが無効化されることはない。
+/// 容量を超えた場合は、最も長い間参照されていないエントリから破棄される。
+pub struct ReconstructedSnapshotCache {
+    capacity: usize,
+    entries: Mutex<CacheEntries>,
+}
+
+#[derive(Default)]
+struct CacheEntries {
+    /// エントリが参照されるたびに増加するカウンタ。
+    clock: u64,
+    /// 統計量の型とデータ点の組から、復元済みのスナップショットと最後に参照された時刻への写像。
+    snapshots: HashMap<CacheKey, CachedSnapshot>,
+    /// 最後に参照された時刻から、そのとき参照されたエントリのキーへの写像。
+    /// 最も長い間参照されていないエントリを、すべてのエントリを走査することなく見つけるために用いる。
+    keys_by_last_use: BTreeMap<u64, CacheKey>,
+}
+
+struct CachedSnapshot {
+    last_used_at: u64,
+    snapshot: Arc<dyn Any + Send + Sync>,
+}
+
+impl CacheEntries {
+    /// `key` のエントリを参照されたものとして記録し、そのスナップショットを返す。
+    fn touch(&mut self, key: &CacheKey) -> Option<Arc<dyn Any + Send + Sync>> {
+        self.clock += 1;
+        let clock = self.clock;
+
+        let cached = self.snapshots.get_mut(key)?;
+        self.keys_by_last_use.remove(&cached.last_used_at);
+        self.keys_by_last_use.insert(clock, *key);
+        cached.last_used_at = clock;
+
+        Some(Arc::clone(&cached.snapshot))
+    }
+
+    fn insert(&mut self, key: CacheKey, snapshot: Arc<dyn Any + Send + Sync>) {
+        self.clock += 1;
+        let clock = self.clock;
+
+        let replaced = self.snapshots.insert(
+            key,
+            CachedSnapshot {
+                last_used_at: clock,
+                snapshot,
+            },
+        );
+        if let Some(replaced) = replaced {
+            self.keys_by_last_use.remove(&replaced.last_used_at);
+        }
+        self.keys_by_last_use.insert(clock, key);
+    }
+
+    fn evict_least_recently_used(&mut self) {
+        if let Some((_, key)) = self.keys_by_last_use.pop_first() {
+            self.snapshots.remove(&key);
+        }
+    }
+}
+
+impl ReconstructedSnapshotCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: Mutex::new(CacheEntries::default()),
+        }
+    }
+
+    pub fn get<Stats: Send + Sync + 'static>(
+        &self,
+        point: SnapshotPointReference,
+    ) -> Option<Arc<StatsSnapshot<Stats>>> {
+        self.entries
+            .lock()
+            .unwrap()
+            .touch(&(TypeId::of::<Stats>(), point))?
+            .downcast::<StatsSnapshot<Stats>>()
+            .ok()
+    }
+
+    pub fn insert<Stats: Send + Sync + 'static>(
+        &self,
+        point: SnapshotPointReference,
+        snapshot: Arc<StatsSnapshot<Stats>>,
+    ) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        let mut entries = self.entries.lock().unwrap();
+        entries.insert((TypeId::of::<Stats>(), point), snapshot);
+
+        while entries.snapshots.len() > self.capacity {
+            entries.evict_least_recently_used();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use domain::models::BreakCount;
+    use domain::test_fixtures::snapshot;
+
+    use super::ReconstructedSnapshotCache;
+    use crate::structures_embedded_in_rdb::SnapshotPointReference;
+
+    fn cached_points(cache: &ReconstructedSnapshotCache, ids: &[u64]) -> Vec<u64> {
+        ids.iter()
+            .copied()
+            .filter(|id| {
+                cache
+                    .get::<BreakCount>(SnapshotPointReference::Full(*id))
+                    .is_some()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn least_recently_used_entry_is_evicted() {
+        let cache = ReconstructedSnapshotCache::new(2);
+        cache.insert(SnapshotPointReference::Full(1), Arc::new(snapshot(1, &[])));
+        cache.insert(SnapshotPointReference::Full(2), Arc::new(snapshot(2, &[])));
+
+        // 1 を参照することで、 2 の方が長い間参照されていないエントリとなる
+        assert!(cache
+            .get::<BreakCount>(SnapshotPointReference::Full(1))
+            .is_some());
+        cache.insert(SnapshotPointReference::Full(3), Arc::new(snapshot(3, &[])));
+
+        assert_eq!(cached_points(&cache, &[1, 2, 3]), vec![1, 3]);
+    }
+
+    #[test]
+    fn reinserting_an_entry_does_not_evict_others() {
+        let cache = ReconstructedSnapshotCache::new(2);
+        cache.insert(SnapshotPointReference::Full(1), Arc::new(snapshot(1, &[])));
+        cache.insert(SnapshotPointReference::Full(2), Arc::new(snapshot(2, &[])));
+        cache.insert(SnapshotPointReference::Full(1), Arc::new(snapshot(1, &[])));
+
+        assert_eq!(cached_points(&cache, &[1, 2]), vec![1, 2]);
+    }
+}
@@ -1,20 +1,74 @@
 use std::collections::{HashMap, HashSet};
 use std::fmt::Debug;
+use std::sync::Arc;
 
 use crate::cycle_free_path::construct_cycle_free_path;
+use crate::snapshot_cache::{unwrap_or_clone, ReconstructedSnapshotCache};
 use crate::structures_embedded_in_rdb::{
-    ComputeDiff, DiffPoint, DiffPointId, DiffSequence, FullSnapshotPoint, IdIndexedDiffPoints,
-    SnapshotPoint,
+    choose_base_diff_sequence_for_snapshot_with_heuristics, snapshot_chunks_after_applying_diffs,
+    ComputeDiff, DiffPoint, DiffPointId, DiffSequence, DiffSequenceChoice, FullSnapshotPoint,
+    IdIndexedDiffPoints, SnapshotPointHeader, SnapshotPointReference,
 };
-use chrono::{DateTime, NaiveDateTime, Utc};
+use anyhow::ensure;
+use chrono::{DateTime, NaiveDateTime, TimeZone, Utc};
 
-use domain::models::{Player, PlayerUuidString, StatsSnapshot};
+use domain::models::{
+    LastSnapshotPointIds, Player, PlayerUuidString, ResampledStats, SnapshotPointId,
+    SnapshotPointMetadata, StatsSnapshot, StatsSnapshotChunks, TimeSeriesPoints,
+    TimeSeriesSampling, TimestampedRank, TimestampedStats,
+};
 use domain::repositories::TimeBasedSnapshotSearchCondition;
-use TimeBasedSnapshotSearchCondition::NewestBefore;
+
+/// `timestamp` に記録されたデータ点が `condition` にどれだけ近いかを表すキー。
+/// 小さいほど条件によく合致し、基準時刻から等しく離れている場合はより古い方が小さくなる。
+fn closeness_to_condition(
+    condition: TimeBasedSnapshotSearchCondition,
+    timestamp: NaiveDateTime,
+) -> (chrono::Duration, NaiveDateTime) {
+    let distance = condition
+        .reference_timestamp()
+        .map_or_else(chrono::Duration::zero, |reference| {
+            (timestamp - reference.naive_utc()).abs()
+        });
+
+    (distance, timestamp)
+}
+
+/// `diff_point_id` の diff point からその根の full snapshot point までの diff point の ID を、
+/// `diff_point_id_to_previous_id_map` を用いてさかのぼるような `Vec` を作る。
+///
+/// 辿った diff point が `diff_point_id_to_previous_id_map` に含まれていない場合はエラーとする。
+fn ids_of_diff_points_towards_root(
+    diff_point_id: DiffPointId,
+    diff_point_id_to_previous_id_map: &HashMap<DiffPointId, Option<DiffPointId>>,
+) -> anyhow::Result<Vec<DiffPointId>> {
+    let path = construct_cycle_free_path(diff_point_id, |id| {
+        diff_point_id_to_previous_id_map.get(&id).copied().flatten()
+    })?;
+
+    // 辿るのを止めた diff point は、直前の diff point を持たないものとして記録されていなければならない
+    if let Some(last_id) = path.last() {
+        anyhow::ensure!(
+            diff_point_id_to_previous_id_map.contains_key(last_id),
+            "diff point {} does not exist",
+            last_id.0
+        );
+    }
+
+    Ok(path)
+}
 
 #[async_trait::async_trait]
 pub trait HasIncrementalSnapshotTables<DBConnection>: Sized + Eq + Clone {
-    async fn create_full_snapshot_point(conn: &mut DBConnection) -> anyhow::Result<u64>;
+    /// この統計量のデータ点の森に書き込むためのロックを、他のプロセスを含むすべての接続の間で排他的に取得する。
+    async fn acquire_writer_lock(conn: &mut DBConnection) -> anyhow::Result<()>;
+
+    async fn release_writer_lock(conn: &mut DBConnection) -> anyhow::Result<()>;
+
+    async fn create_full_snapshot_point(
+        timestamp: DateTime<Utc>,
+        conn: &mut DBConnection,
+    ) -> anyhow::Result<u64>;
 
     async fn insert_all_stats_at_full_snapshot_point(
         fresh_full_snapshot_point_id: u64,
@@ -31,7 +85,7 @@ pub trait HasIncrementalSnapshotTables<DBConnection>: Sized + Eq + Clone {
 
     async fn insert_all_stats_at_diff_snapshot_point(
         fresh_diff_snapshot_point_id: DiffPointId,
-        player_stats_diffs: HashMap<PlayerUuidString, Self>,
+        player_stats_diffs: HashMap<PlayerUuidString, Option<Self>>,
         conn: &mut DBConnection,
     ) -> anyhow::Result<()>;
 
@@ -75,6 +129,58 @@ pub trait HasIncrementalSnapshotTables<DBConnection>: Sized + Eq + Clone {
         timestamp_upper_bound: DateTime<Utc>,
         conn: &mut DBConnection,
     ) -> anyhow::Result<HashMap<DiffPointId, Option<DiffPointId>>>;
+
+    async fn read_header_of_diff_snapshot_point(
+        diff_point_id: DiffPointId,
+        conn: &mut DBConnection,
+    ) -> anyhow::Result<SnapshotPointHeader>;
+
+    async fn find_headers_of_full_snapshot_points_between(
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        conn: &mut DBConnection,
+    ) -> anyhow::Result<Vec<SnapshotPointHeader>>;
+
+    async fn find_headers_of_diff_snapshot_points_between(
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        conn: &mut DBConnection,
+    ) -> anyhow::Result<Vec<SnapshotPointHeader>>;
+
+    async fn find_id_of_last_full_snapshot_point(
+        conn: &mut DBConnection,
+    ) -> anyhow::Result<Option<u64>>;
+
+    async fn find_id_of_last_diff_snapshot_point(
+        conn: &mut DBConnection,
+    ) -> anyhow::Result<Option<DiffPointId>>;
+
+    async fn find_headers_of_full_snapshot_points_with_id_greater_than(
+        last_id: Option<u64>,
+        conn: &mut DBConnection,
+    ) -> anyhow::Result<Vec<SnapshotPointHeader>>;
+
+    async fn find_headers_of_diff_snapshot_points_with_id_greater_than(
+        last_id: Option<DiffPointId>,
+        conn: &mut DBConnection,
+    ) -> anyhow::Result<Vec<SnapshotPointHeader>>;
+
+    async fn read_stats_of_players_at_full_snapshot_points(
+        players: &HashSet<PlayerUuidString>,
+        full_snapshot_point_ids: HashSet<u64>,
+        conn: &mut DBConnection,
+    ) -> anyhow::Result<HashMap<u64, HashMap<PlayerUuidString, Self>>>;
+
+    async fn read_stats_of_players_at_diff_snapshot_points(
+        players: &HashSet<PlayerUuidString>,
+        diff_snapshot_point_ids: HashSet<DiffPointId>,
+        conn: &mut DBConnection,
+    ) -> anyhow::Result<HashMap<DiffPointId, HashMap<PlayerUuidString, Option<Self>>>>;
+
+    async fn count_records_at_diff_snapshot_points(
+        diff_snapshot_point_ids: HashSet<DiffPointId>,
+        conn: &mut DBConnection,
+    ) -> anyhow::Result<HashMap<DiffPointId, u64>>;
 }
 
 #[async_trait::async_trait]
@@ -86,7 +192,8 @@ pub trait HasIncrementalSnapshotTablesDefaultMethods<DBConnection: Send>:
         snapshot: StatsSnapshot<Self>,
         conn: &mut DBConnection,
     ) -> anyhow::Result<()> {
-        let inserted_point_id = Self::create_full_snapshot_point(conn).await?;
+        let inserted_point_id =
+            Self::create_full_snapshot_point(snapshot.utc_timestamp, conn).await?;
 
         Self::insert_all_stats_at_full_snapshot_point(
             inserted_point_id,
@@ -96,6 +203,43 @@ pub trait HasIncrementalSnapshotTablesDefaultMethods<DBConnection: Send>:
         .await
     }
 
+    /// `snapshot` を新たなデータ点としてデータ点の森に追加する。
+    ///
+    /// 基底には `snapshot` より前に記録されたデータ点のみを用い、既存のデータ点は一切変更しない。
+    /// そのため、 `snapshot` が既存のデータ点より前の時刻のものであっても、
+    /// 後続のデータ点はそれぞれの基底を保ったままとなる。
+    /// `snapshot` より前に full snapshot point が存在しない場合、 `snapshot` は新たな根となる。
+    #[tracing::instrument(skip(conn))]
+    async fn insert_snapshot_into_forest(
+        snapshot: StatsSnapshot<Self>,
+        conn: &mut DBConnection,
+    ) -> anyhow::Result<()> {
+        if let Some(full_snapshot) =
+            Self::find_latest_full_snapshot_before(snapshot.utc_timestamp, conn).await?
+        {
+            let diff_points_over_full_snapshot =
+                Self::read_diff_snapshot_points_over_full_point(full_snapshot.id, conn)
+                    .await?
+                    .points_before(snapshot.utc_timestamp);
+
+            let diff_sequence_choice = choose_base_diff_sequence_for_snapshot_with_heuristics(
+                full_snapshot,
+                diff_points_over_full_snapshot,
+                &snapshot,
+            )?;
+
+            if let DiffSequenceChoice::OptimalAccordingToHeuristics(diff_sequence) =
+                diff_sequence_choice
+            {
+                Self::create_diff_snapshot_point_on(diff_sequence, snapshot, conn).await
+            } else {
+                Self::create_full_snapshot(snapshot, conn).await
+            }
+        } else {
+            Self::create_full_snapshot(snapshot, conn).await
+        }
+    }
+
     #[tracing::instrument(skip(conn))]
     async fn find_latest_full_snapshot_before(
         timestamp: DateTime<Utc>,
@@ -109,11 +253,12 @@ pub trait HasIncrementalSnapshotTablesDefaultMethods<DBConnection: Send>:
         }
     }
 
+    /// `time_based_condition` に合致するデータ点を、統計量を読み出すことなく探す。
     #[tracing::instrument(skip(conn))]
-    async fn find_snapshot_point_with_condition(
+    async fn locate_snapshot_point_with_condition(
         time_based_condition: TimeBasedSnapshotSearchCondition,
         conn: &mut DBConnection,
-    ) -> anyhow::Result<Option<SnapshotPoint<Self>>> {
+    ) -> anyhow::Result<Option<(SnapshotPointReference, NaiveDateTime)>> {
         let found_full_snapshot_point =
             Self::find_id_and_timestamp_of_full_snapshot_point_with_condition(
                 time_based_condition,
@@ -128,35 +273,49 @@ pub trait HasIncrementalSnapshotTablesDefaultMethods<DBConnection: Send>:
             )
             .await?;
 
-        match (found_full_snapshot_point, found_diff_snapshot_point) {
-            (None, None) => return Ok(None),
-            (Some((full_id, _)), None) => {
-                // full snapshot point の方を採用する
-                Ok(Some(SnapshotPoint::Full(
-                    Self::read_full_snapshot_point(full_id, conn).await?,
-                )))
-            }
-            (Some((full_id, full_timestamp)), Some((_, diff_timestamp)))
-                if matches!(time_based_condition, NewestBefore(_))
-                    && full_timestamp > diff_timestamp =>
-            {
-                // full snapshot point の方を採用する
-                Ok(Some(SnapshotPoint::Full(
-                    Self::read_full_snapshot_point(full_id, conn).await?,
-                )))
-            }
-            (_, Some((diff_id, _))) => {
-                // 条件に合致する full snapshot point が存在しないか、
-                // diff snapshot point の方が time_based_condition の timestamp に近いため、
-                // diff snapshot point の方を採用する
-                let diff_point =
-                    Self::read_diff_snapshot_points(vec![diff_id].into_iter().collect(), conn)
-                        .await?
-                        .remove(&diff_id)
-                        .unwrap();
+        Ok(
+            match (found_full_snapshot_point, found_diff_snapshot_point) {
+                (None, None) => None,
+                (Some((full_id, full_timestamp)), None) => {
+                    Some((SnapshotPointReference::Full(full_id), full_timestamp))
+                }
+                (None, Some((diff_id, diff_timestamp))) => {
+                    Some((SnapshotPointReference::Diff(diff_id), diff_timestamp))
+                }
+                (Some((full_id, full_timestamp)), Some((diff_id, diff_timestamp))) => {
+                    // 条件の基準時刻により近い方を採用する。
+                    // 等しく近い場合は、差分を辿らずに復元できる full snapshot point の方を採用する
+                    if closeness_to_condition(time_based_condition, diff_timestamp)
+                        < closeness_to_condition(time_based_condition, full_timestamp)
+                    {
+                        Some((SnapshotPointReference::Diff(diff_id), diff_timestamp))
+                    } else {
+                        Some((SnapshotPointReference::Full(full_id), full_timestamp))
+                    }
+                }
+            },
+        )
+    }
 
-                Ok(Some(SnapshotPoint::Diff(diff_point)))
+    /// `time_based_condition` に合致するデータ点の、データ点の森の中での位置と時刻を探す。
+    #[tracing::instrument(skip(conn))]
+    async fn find_header_of_snapshot_point_with_condition(
+        time_based_condition: TimeBasedSnapshotSearchCondition,
+        conn: &mut DBConnection,
+    ) -> anyhow::Result<Option<SnapshotPointHeader>> {
+        match Self::locate_snapshot_point_with_condition(time_based_condition, conn).await? {
+            None => Ok(None),
+            Some((SnapshotPointReference::Full(full_id), full_timestamp)) => {
+                Ok(Some(SnapshotPointHeader {
+                    reference: SnapshotPointReference::Full(full_id),
+                    root_full_snapshot_point_id: full_id,
+                    previous_diff_point_id: None,
+                    utc_timestamp: Utc.from_utc_datetime(&full_timestamp),
+                }))
             }
+            Some((SnapshotPointReference::Diff(diff_id), _)) => Ok(Some(
+                Self::read_header_of_diff_snapshot_point(diff_id, conn).await?,
+            )),
         }
     }
 
@@ -166,6 +325,15 @@ pub trait HasIncrementalSnapshotTablesDefaultMethods<DBConnection: Send>:
         snapshot: StatsSnapshot<Self>,
         conn: &mut DBConnection,
     ) -> anyhow::Result<()> {
+        // データ点の森において、基底となるデータ点の時刻は子のデータ点の時刻未満でなければならない
+        let base_timestamp = diff_sequence.utc_timestamp_at_the_tip();
+        ensure!(
+            base_timestamp < snapshot.utc_timestamp,
+            "snapshot at {} cannot be recorded over a point at {}",
+            snapshot.utc_timestamp,
+            base_timestamp
+        );
+
         let root_point_id = diff_sequence.base_point.id;
         let previous_point_id = diff_sequence.diff_points.last().map(|p| p.id);
         let diff = diff_sequence.into_snapshot_at_the_tip().diff_to(&snapshot);
@@ -186,19 +354,173 @@ pub trait HasIncrementalSnapshotTablesDefaultMethods<DBConnection: Send>:
         .await
     }
 
-    #[tracing::instrument(skip(conn))]
-    async fn construct_diff_sequence_leading_up_to(
-        snapshot_point: SnapshotPoint<Self>,
+    /// `time_based_condition` に合致するデータ点でのスナップショットを、
+    /// `reconstruct_snapshot_at` と同様にして復元する。
+    #[tracing::instrument(skip(cache, conn))]
+    async fn reconstruct_snapshot_with_condition(
+        time_based_condition: TimeBasedSnapshotSearchCondition,
+        cache: &ReconstructedSnapshotCache,
         conn: &mut DBConnection,
-    ) -> anyhow::Result<DiffSequence<Self>> {
-        match snapshot_point {
-            SnapshotPoint::Full(snapshot_point) => {
-                Ok(DiffSequence::without_any_diffs(snapshot_point))
+    ) -> anyhow::Result<Option<Arc<StatsSnapshot<Self>>>>
+    where
+        Self: Send + Sync + 'static,
+    {
+        match Self::find_header_of_snapshot_point_with_condition(time_based_condition, conn).await?
+        {
+            Some(snapshot_point) => Ok(Some(
+                Self::reconstruct_snapshot_at(snapshot_point, cache, conn).await?,
+            )),
+            None => Ok(None),
+        }
+    }
+
+    /// `snapshot_point` でのスナップショットを復元する。
+    ///
+    /// データ点またはその祖先のスナップショットが `cache` にあれば、
+    /// 最も近いものから先の差分のみを読み出して適用する。
+    /// 復元されたスナップショットは `cache` に追加される。
+    #[tracing::instrument(skip(cache, conn))]
+    async fn reconstruct_snapshot_at(
+        snapshot_point: SnapshotPointHeader,
+        cache: &ReconstructedSnapshotCache,
+        conn: &mut DBConnection,
+    ) -> anyhow::Result<Arc<StatsSnapshot<Self>>>
+    where
+        Self: Send + Sync + 'static,
+    {
+        let (base_snapshot, remaining_diff_points) =
+            Self::find_nearest_cached_ancestor(&snapshot_point, cache, conn).await?;
+
+        if remaining_diff_points.is_empty() {
+            return Ok(base_snapshot);
+        }
+
+        let mut snapshot = unwrap_or_clone(base_snapshot);
+        for diff_point in remaining_diff_points {
+            snapshot = diff_point.diff.apply_to(snapshot);
+        }
+
+        let snapshot = Arc::new(snapshot);
+        cache.insert(snapshot_point.reference, Arc::clone(&snapshot));
+
+        Ok(snapshot)
+    }
+
+    /// `time_based_condition` に合致するデータ点でのスナップショットを、
+    /// スナップショット全体を一度に構築することなく、高々 `chunk_size` 人分ずつに分割して取り出せるようにする。
+    ///
+    /// `reconstruct_snapshot_at` と同様に、 `cache` にある最も近い祖先から先の差分のみを読み出す。
+    #[tracing::instrument(skip(cache, conn))]
+    async fn reconstruct_snapshot_chunks_with_condition(
+        time_based_condition: TimeBasedSnapshotSearchCondition,
+        chunk_size: usize,
+        cache: &ReconstructedSnapshotCache,
+        conn: &mut DBConnection,
+    ) -> anyhow::Result<Option<StatsSnapshotChunks<Self>>>
+    where
+        Self: Send + Sync + 'static,
+    {
+        let snapshot_point =
+            match Self::find_header_of_snapshot_point_with_condition(time_based_condition, conn)
+                .await?
+            {
+                Some(snapshot_point) => snapshot_point,
+                None => return Ok(None),
+            };
+
+        let (base_snapshot, remaining_diff_points) =
+            Self::find_nearest_cached_ancestor(&snapshot_point, cache, conn).await?;
+
+        Ok(Some(snapshot_chunks_after_applying_diffs(
+            unwrap_or_clone(base_snapshot),
+            remaining_diff_points,
+            chunk_size,
+        )))
+    }
+
+    /// `cache` にある、 `snapshot_point` の最も近い祖先 (データ点自身を含む) のスナップショットと、
+    /// そこから `snapshot_point` までに順に適用すべき diff point を返す。
+    ///
+    /// キャッシュに祖先が一つも無い場合は根の full snapshot point を読み出し、 `cache` に追加した上で起点とする。
+    #[tracing::instrument(skip(cache, conn))]
+    async fn find_nearest_cached_ancestor(
+        snapshot_point: &SnapshotPointHeader,
+        cache: &ReconstructedSnapshotCache,
+        conn: &mut DBConnection,
+    ) -> anyhow::Result<(Arc<StatsSnapshot<Self>>, Vec<DiffPoint<Self>>)>
+    where
+        Self: Send + Sync + 'static,
+    {
+        let root_point_id = snapshot_point.root_full_snapshot_point_id;
+
+        // データ点からその根の full snapshot point までの diff point の ID をさかのぼるような `Vec`。
+        let ids_of_diff_points_towards_root = match snapshot_point.reference {
+            SnapshotPointReference::Full(_) => Vec::new(),
+            SnapshotPointReference::Diff(diff_point_id) => {
+                let diff_point_id_to_previous_id_map =
+                    Self::diff_point_id_to_previous_diff_point_id(
+                        root_point_id,
+                        snapshot_point.utc_timestamp,
+                        conn,
+                    )
+                    .await?;
+
+                ids_of_diff_points_towards_root(diff_point_id, &diff_point_id_to_previous_id_map)?
             }
-            SnapshotPoint::Diff(diff_point) => {
-                Self::construct_diff_sequence_leading_up_to_diff_point(diff_point, conn).await
+        };
+
+        // キャッシュにある最も近い祖先 (データ点自身を含む) と、そこから先端までに適用すべき diff point
+        let cached_ancestor = ids_of_diff_points_towards_root
+            .iter()
+            .enumerate()
+            .find_map(|(index, id)| {
+                cache
+                    .get::<Self>(SnapshotPointReference::Diff(*id))
+                    .map(|snapshot| (index, snapshot))
+            })
+            .or_else(|| {
+                cache
+                    .get::<Self>(SnapshotPointReference::Full(root_point_id))
+                    .map(|snapshot| (ids_of_diff_points_towards_root.len(), snapshot))
+            });
+
+        let (base_snapshot, ids_of_remaining_diff_points_towards_root) = match cached_ancestor {
+            Some((index, snapshot)) => (snapshot, &ids_of_diff_points_towards_root[..index]),
+            None => {
+                let full_snapshot = Arc::new(
+                    Self::read_full_snapshot_point(root_point_id, conn)
+                        .await?
+                        .full_snapshot,
+                );
+                cache.insert(
+                    SnapshotPointReference::Full(root_point_id),
+                    Arc::clone(&full_snapshot),
+                );
+
+                (full_snapshot, &ids_of_diff_points_towards_root[..])
             }
+        };
+
+        if ids_of_remaining_diff_points_towards_root.is_empty() {
+            return Ok((base_snapshot, Vec::new()));
         }
+
+        let ids_of_remaining_diff_points_towards_tip = ids_of_remaining_diff_points_towards_root
+            .iter()
+            .rev()
+            .copied()
+            .collect::<Vec<_>>();
+        let remaining_diff_points = Self::read_diff_snapshot_points(
+            ids_of_remaining_diff_points_towards_tip
+                .iter()
+                .copied()
+                .collect(),
+            conn,
+        )
+        .await?
+        .map_ids_to_diff_points(&ids_of_remaining_diff_points_towards_tip)?;
+
+        Ok((base_snapshot, remaining_diff_points))
     }
 
     #[tracing::instrument(skip(conn))]
@@ -218,7 +540,7 @@ pub trait HasIncrementalSnapshotTablesDefaultMethods<DBConnection: Send>:
         // `diff_point` に対応する diff point からその root full snapshot point までの
         // diff point の ID をさかのぼるような `Vec`。
         let ids_of_diff_points_towards_root =
-            construct_cycle_free_path(diff_point.id, |id| diff_point_id_to_previous_id_map[&id])?;
+            ids_of_diff_points_towards_root(diff_point.id, &diff_point_id_to_previous_id_map)?;
 
         let diff_points_towards_given_point = {
             let ids_of_diff_points_towards_tip = {
@@ -230,7 +552,7 @@ pub trait HasIncrementalSnapshotTablesDefaultMethods<DBConnection: Send>:
             let ids_set = ids_of_diff_points_towards_tip.iter().copied().collect();
             Self::read_diff_snapshot_points(ids_set, conn)
                 .await?
-                .map_ids_to_diff_points(&ids_of_diff_points_towards_tip)
+                .map_ids_to_diff_points(&ids_of_diff_points_towards_tip)?
         };
 
         let full_snapshot = Self::read_full_snapshot_point(root_point_id, conn).await?;
@@ -239,6 +561,523 @@ pub trait HasIncrementalSnapshotTablesDefaultMethods<DBConnection: Send>:
             diff_points_towards_given_point,
         ))
     }
+
+    /// `snapshot_points` のそれぞれについて、 `players` に含まれるプレーヤーの統計量を読み出す。
+    ///
+    /// データ点ごとにスナップショット全体を復元するのではなく、
+    /// 根の full snapshot point とそこからデータ点までの diff point にある
+    /// `players` のレコードのみを読み出して統計量を求める。
+    #[tracing::instrument(skip(players, snapshot_points, conn))]
+    async fn read_stats_of_players_at_snapshot_points(
+        players: &HashSet<PlayerUuidString>,
+        snapshot_points: Vec<SnapshotPointHeader>,
+        conn: &mut DBConnection,
+    ) -> anyhow::Result<Vec<(SnapshotPointHeader, HashMap<PlayerUuidString, Self>)>> {
+        if snapshot_points.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let timestamp_upper_bound = snapshot_points
+            .iter()
+            .map(|point| point.utc_timestamp)
+            .max()
+            .unwrap();
+
+        let roots_of_diff_points = snapshot_points
+            .iter()
+            .filter(|point| matches!(point.reference, SnapshotPointReference::Diff(_)))
+            .map(|point| point.root_full_snapshot_point_id)
+            .collect::<HashSet<_>>();
+
+        let mut diff_point_id_to_previous_id_map = HashMap::new();
+        for root_point_id in roots_of_diff_points {
+            diff_point_id_to_previous_id_map.extend(
+                Self::diff_point_id_to_previous_diff_point_id(
+                    root_point_id,
+                    timestamp_upper_bound,
+                    conn,
+                )
+                .await?,
+            );
+        }
+
+        // 各データ点からその根の full snapshot point までの diff point の ID をさかのぼるような `Vec` の列。
+        let ids_of_diff_points_towards_root = snapshot_points
+            .iter()
+            .map(|point| match point.reference {
+                SnapshotPointReference::Full(_) => Ok(Vec::new()),
+                SnapshotPointReference::Diff(diff_point_id) => ids_of_diff_points_towards_root(
+                    diff_point_id,
+                    &diff_point_id_to_previous_id_map,
+                ),
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        let stats_at_diff_points = Self::read_stats_of_players_at_diff_snapshot_points(
+            players,
+            ids_of_diff_points_towards_root
+                .iter()
+                .flatten()
+                .copied()
+                .collect(),
+            conn,
+        )
+        .await?;
+
+        let stats_at_full_snapshot_points = Self::read_stats_of_players_at_full_snapshot_points(
+            players,
+            snapshot_points
+                .iter()
+                .map(|point| point.root_full_snapshot_point_id)
+                .collect(),
+            conn,
+        )
+        .await?;
+
+        Ok(snapshot_points
+            .into_iter()
+            .zip(ids_of_diff_points_towards_root)
+            .map(|(point, ids_towards_root)| {
+                let mut player_stats = stats_at_full_snapshot_points
+                    .get(&point.root_full_snapshot_point_id)
+                    .cloned()
+                    .unwrap_or_default();
+
+                // 根に近い diff point から順に差分を適用していく
+                for diff_point_id in ids_towards_root.iter().rev() {
+                    if let Some(player_stats_diffs) = stats_at_diff_points.get(diff_point_id) {
+                        for (player_uuid, stats) in player_stats_diffs {
+                            match stats {
+                                Some(stats) => {
+                                    player_stats.insert(*player_uuid, stats.clone());
+                                }
+                                None => {
+                                    player_stats.remove(player_uuid);
+                                }
+                            }
+                        }
+                    }
+                }
+
+                (point, player_stats)
+            })
+            .collect())
+    }
+
+    #[tracing::instrument(skip(conn))]
+    async fn read_stats_history_of_player(
+        player_uuid: PlayerUuidString,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        conn: &mut DBConnection,
+    ) -> anyhow::Result<Vec<TimestampedStats<Self>>> {
+        let mut snapshot_points =
+            Self::find_headers_of_full_snapshot_points_between(from, to, conn).await?;
+        snapshot_points
+            .extend(Self::find_headers_of_diff_snapshot_points_between(from, to, conn).await?);
+        snapshot_points.sort_by_key(SnapshotPointHeader::ordering_key);
+
+        let players = HashSet::from([player_uuid]);
+        let stats_at_snapshot_points =
+            Self::read_stats_of_players_at_snapshot_points(&players, snapshot_points, conn).await?;
+
+        Ok(stats_at_snapshot_points
+            .into_iter()
+            .filter_map(|(point, mut player_stats)| {
+                player_stats
+                    .remove(&player_uuid)
+                    .map(|stats| TimestampedStats {
+                        utc_timestamp: point.utc_timestamp,
+                        stats,
+                    })
+            })
+            .collect())
+    }
+
+    /// `time_based_condition` に合致するデータ点での `player_uuid` の統計量を読み出す。
+    ///
+    /// データ点から `previous_diff_point_id` を根の full snapshot point まで辿り、
+    /// その経路上にある `player_uuid` のレコードのみを読み出す。
+    #[tracing::instrument(skip(conn))]
+    async fn read_stats_of_player_with_condition(
+        player_uuid: PlayerUuidString,
+        time_based_condition: TimeBasedSnapshotSearchCondition,
+        conn: &mut DBConnection,
+    ) -> anyhow::Result<Option<TimestampedStats<Self>>> {
+        let snapshot_point =
+            Self::find_header_of_snapshot_point_with_condition(time_based_condition, conn).await?;
+
+        let players = HashSet::from([player_uuid]);
+        let stats_at_snapshot_point = Self::read_stats_of_players_at_snapshot_points(
+            &players,
+            snapshot_point.into_iter().collect(),
+            conn,
+        )
+        .await?;
+
+        Ok(stats_at_snapshot_point
+            .into_iter()
+            .next()
+            .and_then(|(point, mut player_stats)| {
+                player_stats
+                    .remove(&player_uuid)
+                    .map(|stats| TimestampedStats {
+                        utc_timestamp: point.utc_timestamp,
+                        stats,
+                    })
+            }))
+    }
+
+    /// `time_based_condition` に合致するデータ点でのスナップショットのうち、
+    /// `players` に含まれるプレーヤーの統計量のみを読み出す。
+    ///
+    /// スナップショット全体を復元するのではなく、根の full snapshot point からデータ点までの経路上にある
+    /// `players` のレコードのみを読み出す。
+    #[tracing::instrument(skip(players, conn), fields(player_count = players.len()))]
+    async fn read_snapshot_of_players_with_condition(
+        players: &HashSet<PlayerUuidString>,
+        time_based_condition: TimeBasedSnapshotSearchCondition,
+        conn: &mut DBConnection,
+    ) -> anyhow::Result<Option<StatsSnapshot<Self>>> {
+        let snapshot_point =
+            Self::find_header_of_snapshot_point_with_condition(time_based_condition, conn).await?;
+
+        let stats_at_snapshot_point = Self::read_stats_of_players_at_snapshot_points(
+            players,
+            snapshot_point.into_iter().collect(),
+            conn,
+        )
+        .await?;
+
+        Ok(stats_at_snapshot_point
+            .into_iter()
+            .next()
+            .map(|(point, player_stats)| StatsSnapshot {
+                utc_timestamp: point.utc_timestamp,
+                player_stats: player_stats
+                    .into_iter()
+                    .map(|(uuid, stats)| (Player { uuid }, stats))
+                    .collect(),
+            }))
+    }
+
+    /// `sampling` が定める各時刻と、その時刻以前で最も新しいデータ点の組を、時刻の昇順に返す。
+    ///
+    /// 標本ごとにデータ点を検索するのではなく、期間中のデータ点をまとめて列挙した上で対応付ける。
+    #[tracing::instrument(skip(conn))]
+    async fn find_headers_of_sampled_snapshot_points(
+        sampling: TimeSeriesSampling,
+        conn: &mut DBConnection,
+    ) -> anyhow::Result<Vec<(DateTime<Utc>, Option<SnapshotPointHeader>)>> {
+        // 最初の標本の値となるデータ点と、それ以降期間中に記録されたデータ点
+        let mut snapshot_points = Vec::new();
+        snapshot_points.extend(
+            Self::find_header_of_snapshot_point_with_condition(
+                TimeBasedSnapshotSearchCondition::NewestBefore(sampling.from()),
+                conn,
+            )
+            .await?,
+        );
+        {
+            let mut points_in_range = Self::find_headers_of_full_snapshot_points_between(
+                sampling.from(),
+                sampling.to(),
+                conn,
+            )
+            .await?;
+            points_in_range.extend(
+                Self::find_headers_of_diff_snapshot_points_between(
+                    sampling.from(),
+                    sampling.to(),
+                    conn,
+                )
+                .await?,
+            );
+            points_in_range.retain(|point| point.utc_timestamp > sampling.from());
+            // 同じ時刻に記録されたデータ点のうち、 full snapshot point の方が後に来るようにする
+            points_in_range.sort_by_key(SnapshotPointHeader::ordering_key);
+            snapshot_points.extend(points_in_range);
+        }
+
+        let mut snapshot_points = snapshot_points.into_iter().peekable();
+        let mut newest_point = None;
+        Ok(sampling
+            .sample_timestamps()
+            .map(|sample_timestamp| {
+                while let Some(point) =
+                    snapshot_points.next_if(|point| point.utc_timestamp <= sample_timestamp)
+                {
+                    newest_point = Some(point);
+                }
+                (sample_timestamp, newest_point.clone())
+            })
+            .collect())
+    }
+
+    /// `sampling` が定める各時刻について、その時刻以前で最も新しいデータ点での `players` の統計量を読み出す。
+    ///
+    /// 標本の値として用いられるデータ点での `players` の統計量は、一度にまとめて読み出される。
+    #[tracing::instrument(skip(players, conn))]
+    async fn read_resampled_stats_of_players(
+        players: &HashSet<PlayerUuidString>,
+        sampling: TimeSeriesSampling,
+        conn: &mut DBConnection,
+    ) -> anyhow::Result<Vec<ResampledStats<Self>>> {
+        let samples = Self::find_headers_of_sampled_snapshot_points(sampling, conn).await?;
+
+        let mut sampled_points = samples
+            .iter()
+            .filter_map(|(_, point)| point.clone())
+            .collect::<Vec<_>>();
+        sampled_points.dedup_by_key(|point| point.reference);
+
+        let stats_at_sampled_points =
+            Self::read_stats_of_players_at_snapshot_points(players, sampled_points, conn)
+                .await?
+                .into_iter()
+                .map(|(point, player_stats)| (point.reference, player_stats))
+                .collect::<HashMap<_, _>>();
+
+        Ok(samples
+            .into_iter()
+            .map(|(sample_utc_timestamp, point)| ResampledStats {
+                sample_utc_timestamp,
+                snapshot_utc_timestamp: point.as_ref().map(|point| point.utc_timestamp),
+                player_stats: point
+                    .and_then(|point| stats_at_sampled_points.get(&point.reference))
+                    .map(|player_stats| {
+                        player_stats
+                            .iter()
+                            .map(|(uuid, stats)| (Player { uuid: *uuid }, stats.clone()))
+                            .collect()
+                    })
+                    .unwrap_or_default(),
+            })
+            .collect())
+    }
+
+    /// `points` が定める各時刻について、その時刻でのデータ点における `player_uuid` の順位を計算する。
+    ///
+    /// データ点ごとにスナップショット全体を復元し直すのではなく、
+    /// 直前のデータ点が親であればその差分のみを適用してスナップショットを更新していく。
+    #[tracing::instrument(skip(cache, conn))]
+    async fn read_rank_history_of_player(
+        player_uuid: PlayerUuidString,
+        points: TimeSeriesPoints,
+        cache: &ReconstructedSnapshotCache,
+        conn: &mut DBConnection,
+    ) -> anyhow::Result<Vec<TimestampedRank>>
+    where
+        Self: Ord + Send + Sync + 'static,
+    {
+        let points = match points {
+            TimeSeriesPoints::Recorded { from, to } => {
+                let mut snapshot_points =
+                    Self::find_headers_of_full_snapshot_points_between(from, to, conn).await?;
+                snapshot_points.extend(
+                    Self::find_headers_of_diff_snapshot_points_between(from, to, conn).await?,
+                );
+                snapshot_points.sort_by_key(SnapshotPointHeader::ordering_key);
+
+                snapshot_points
+                    .into_iter()
+                    .map(|point| (point.utc_timestamp, Some(point)))
+                    .collect()
+            }
+            TimeSeriesPoints::Sampled(sampling) => {
+                Self::find_headers_of_sampled_snapshot_points(sampling, conn).await?
+            }
+        };
+
+        let player = Player { uuid: player_uuid };
+        // キャッシュから得たスナップショットは、差分を適用する必要が生じるまで複製せずに参照する
+        let mut current_snapshot: Option<(SnapshotPointReference, Arc<StatsSnapshot<Self>>)> = None;
+        let mut rank_history = Vec::with_capacity(points.len());
+
+        for (utc_timestamp, point) in points {
+            let point = match point {
+                Some(point) => point,
+                None => {
+                    rank_history.push(TimestampedRank {
+                        utc_timestamp,
+                        snapshot_utc_timestamp: None,
+                        rank: None,
+                        total_ranked_player_count: 0,
+                    });
+                    continue;
+                }
+            };
+
+            let current_reference = current_snapshot.as_ref().map(|(reference, _)| *reference);
+            let is_child_of_current_point = match (current_reference, point.reference) {
+                (
+                    Some(SnapshotPointReference::Full(current_id)),
+                    SnapshotPointReference::Diff(_),
+                ) => {
+                    point.previous_diff_point_id.is_none()
+                        && point.root_full_snapshot_point_id == current_id
+                }
+                (
+                    Some(SnapshotPointReference::Diff(current_id)),
+                    SnapshotPointReference::Diff(_),
+                ) => point.previous_diff_point_id == Some(current_id),
+                _ => false,
+            };
+
+            if current_reference != Some(point.reference) {
+                let snapshot = match (current_snapshot.take(), point.reference) {
+                    (Some((_, snapshot)), SnapshotPointReference::Diff(diff_point_id))
+                        if is_child_of_current_point =>
+                    {
+                        let diff_point =
+                            Self::read_diff_snapshot_points(HashSet::from([diff_point_id]), conn)
+                                .await?
+                                .remove(&diff_point_id)
+                                .ok_or_else(|| {
+                                    anyhow::anyhow!("diff point {} does not exist", diff_point_id.0)
+                                })?;
+
+                        Arc::new(diff_point.diff.apply_to(unwrap_or_clone(snapshot)))
+                    }
+                    _ => Self::reconstruct_snapshot_at(point.clone(), cache, conn).await?,
+                };
+                current_snapshot = Some((point.reference, snapshot));
+            }
+
+            let (_, snapshot) = current_snapshot.as_ref().unwrap();
+            rank_history.push(TimestampedRank {
+                utc_timestamp,
+                snapshot_utc_timestamp: Some(point.utc_timestamp),
+                rank: snapshot.rank_of(&player),
+                total_ranked_player_count: snapshot.len() as u64,
+            });
+        }
+
+        Ok(rank_history)
+    }
+
+    /// `point` に記録されている統計量のレコードを読み出す。
+    /// diff point に記録されている tombstone は `None` として返す。
+    #[tracing::instrument(skip(conn))]
+    async fn read_records_at_snapshot_point(
+        point: SnapshotPointId,
+        conn: &mut DBConnection,
+    ) -> anyhow::Result<HashMap<PlayerUuidString, Option<Self>>> {
+        match point {
+            SnapshotPointId::Full(id) => Ok(Self::read_full_snapshot_point(id, conn)
+                .await?
+                .full_snapshot
+                .player_stats
+                .into_iter()
+                .map(|(player, stats)| (player.uuid, Some(stats)))
+                .collect()),
+            SnapshotPointId::Diff(id) => {
+                let diff_point_id = DiffPointId(id);
+                let diff_point =
+                    Self::read_diff_snapshot_points(HashSet::from([diff_point_id]), conn)
+                        .await?
+                        .remove(&diff_point_id)
+                        .ok_or_else(|| anyhow::anyhow!("diff point {id} does not exist"))?;
+
+                Ok(diff_point.diff.player_stats_diffs)
+            }
+        }
+    }
+
+    #[tracing::instrument(skip(conn))]
+    async fn list_snapshot_points_between(
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        conn: &mut DBConnection,
+    ) -> anyhow::Result<Vec<SnapshotPointMetadata>> {
+        let full_snapshot_points =
+            Self::find_headers_of_full_snapshot_points_between(from, to, conn).await?;
+        let diff_points =
+            Self::find_headers_of_diff_snapshot_points_between(from, to, conn).await?;
+
+        let mut snapshot_points =
+            Self::headers_to_snapshot_point_metadata(full_snapshot_points, diff_points, conn)
+                .await?;
+        snapshot_points.sort_by_key(|point| match point {
+            SnapshotPointMetadata::Full { id, utc_timestamp } => (*utc_timestamp, true, *id),
+            SnapshotPointMetadata::Diff {
+                id, utc_timestamp, ..
+            } => (*utc_timestamp, false, *id),
+        });
+
+        Ok(snapshot_points)
+    }
+
+    #[tracing::instrument(skip(conn))]
+    async fn find_last_snapshot_point_ids(
+        conn: &mut DBConnection,
+    ) -> anyhow::Result<LastSnapshotPointIds> {
+        Ok(LastSnapshotPointIds {
+            full: Self::find_id_of_last_full_snapshot_point(conn).await?,
+            diff: Self::find_id_of_last_diff_snapshot_point(conn)
+                .await?
+                .map(|id| id.0),
+        })
+    }
+
+    /// `last_ids` より大きな ID を持つデータ点の情報を、
+    /// full snapshot point、 diff point の順に、それぞれ ID の昇順で返す。
+    #[tracing::instrument(skip(conn))]
+    async fn list_snapshot_points_recorded_after(
+        last_ids: LastSnapshotPointIds,
+        conn: &mut DBConnection,
+    ) -> anyhow::Result<Vec<SnapshotPointMetadata>> {
+        let full_snapshot_points =
+            Self::find_headers_of_full_snapshot_points_with_id_greater_than(last_ids.full, conn)
+                .await?;
+        let diff_points = Self::find_headers_of_diff_snapshot_points_with_id_greater_than(
+            last_ids.diff.map(DiffPointId),
+            conn,
+        )
+        .await?;
+
+        Self::headers_to_snapshot_point_metadata(full_snapshot_points, diff_points, conn).await
+    }
+
+    /// データ点のヘッダを、 diff point の差分レコードの数を数えた上で、
+    /// `full_snapshot_points`、 `diff_points` の順に `SnapshotPointMetadata` へと変換する。
+    #[tracing::instrument(skip_all)]
+    async fn headers_to_snapshot_point_metadata(
+        full_snapshot_points: Vec<SnapshotPointHeader>,
+        diff_points: Vec<SnapshotPointHeader>,
+        conn: &mut DBConnection,
+    ) -> anyhow::Result<Vec<SnapshotPointMetadata>> {
+        let diff_record_counts = Self::count_records_at_diff_snapshot_points(
+            diff_points
+                .iter()
+                .filter_map(|point| match point.reference {
+                    SnapshotPointReference::Diff(id) => Some(id),
+                    SnapshotPointReference::Full(_) => None,
+                })
+                .collect(),
+            conn,
+        )
+        .await?;
+
+        Ok(full_snapshot_points
+            .into_iter()
+            .chain(diff_points)
+            .map(|point| match point.reference {
+                SnapshotPointReference::Full(id) => SnapshotPointMetadata::Full {
+                    id,
+                    utc_timestamp: point.utc_timestamp,
+                },
+                SnapshotPointReference::Diff(id) => SnapshotPointMetadata::Diff {
+                    id: id.0,
+                    utc_timestamp: point.utc_timestamp,
+                    root_full_snapshot_point_id: point.root_full_snapshot_point_id,
+                    previous_diff_point_id: point.previous_diff_point_id.map(|id| id.0),
+                    // 差分レコードを一つも持たない diff point も存在しうる
+                    diff_record_count: diff_record_counts.get(&id).copied().unwrap_or(0),
+                },
+            })
+            .collect())
+    }
 }
 
 impl<T: Debug + HasIncrementalSnapshotTables<DBConnection>, DBConnection: Send>
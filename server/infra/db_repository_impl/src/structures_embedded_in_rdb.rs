@@ -2,7 +2,7 @@ use std::collections::{HashMap, HashSet};
 use std::fmt::Debug;
 
 use chrono::{DateTime, Utc};
-use domain::models::{Player, PlayerUuidString, StatsSnapshot};
+use domain::models::{Player, PlayerUuidString, StatsSnapshot, StatsSnapshotChunks};
 use ordered_float::OrderedFloat;
 
 #[derive(Clone)]
@@ -22,7 +22,9 @@ impl<Stats: Debug> Debug for FullSnapshotPoint<Stats> {
 
 pub struct SnapshotDiff<Stats> {
     pub utc_timestamp: DateTime<Utc>,
-    pub player_stats_diffs: HashMap<PlayerUuidString, Stats>,
+    /// 統計量が変化したプレーヤーの新しい統計量。
+    /// 直前のスナップショットから消えたプレーヤーについては、削除を表す `None` (tombstone) を持つ。
+    pub player_stats_diffs: HashMap<PlayerUuidString, Option<Stats>>,
 }
 
 impl<Stats: Debug> Debug for SnapshotDiff<Stats> {
@@ -46,9 +48,15 @@ impl<Stats: Clone> SnapshotDiff<Stats> {
 
     pub fn apply_to_mut(&self, base_snapshot: &mut StatsSnapshot<Stats>) {
         for (player_uuid, diff) in &self.player_stats_diffs {
-            base_snapshot
-                .player_stats
-                .insert(Player { uuid: *player_uuid }, diff.clone());
+            let player = Player { uuid: *player_uuid };
+            match diff {
+                Some(stats) => {
+                    base_snapshot.player_stats.insert(player, stats.clone());
+                }
+                None => {
+                    base_snapshot.player_stats.remove(&player);
+                }
+            }
         }
     }
 }
@@ -69,7 +77,13 @@ impl<Stats: Eq + Clone> ComputeDiff for StatsSnapshot<Stats> {
 
         for (player, stats) in &other.player_stats {
             if Some(stats) != self.player_stats.get(&player) {
-                player_stats_diffs.insert(player.uuid, stats.clone());
+                player_stats_diffs.insert(player.uuid, Some(stats.clone()));
+            }
+        }
+
+        for player in self.player_stats.keys() {
+            if !other.player_stats.contains_key(player) {
+                player_stats_diffs.insert(player.uuid, None);
             }
         }
 
@@ -104,10 +118,33 @@ pub struct DiffPoint<Stats> {
     pub diff: SnapshotDiff<Stats>,
 }
 
-#[derive(Debug)]
-pub enum SnapshotPoint<Stats> {
-    Full(FullSnapshotPoint<Stats>),
-    Diff(DiffPoint<Stats>),
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum SnapshotPointReference {
+    Full(u64),
+    Diff(DiffPointId),
+}
+
+/// 統計量のレコードを含まない、データ点の森の中での位置と時刻のみを表す構造体。
+#[derive(Clone, Debug)]
+pub struct SnapshotPointHeader {
+    pub reference: SnapshotPointReference,
+    /// データ点の根となる full snapshot point の ID。
+    /// データ点自身が full snapshot point である場合は、そのデータ点の ID。
+    pub root_full_snapshot_point_id: u64,
+    pub previous_diff_point_id: Option<DiffPointId>,
+    pub utc_timestamp: DateTime<Utc>,
+}
+
+impl SnapshotPointHeader {
+    /// データ点を時系列順に並べるためのキー。
+    /// 同じ時刻に記録されたデータ点は、 diff point、full snapshot point の順に、
+    /// 同じ種類の中では ID の順に並ぶ。
+    pub fn ordering_key(&self) -> (DateTime<Utc>, bool, u64) {
+        match self.reference {
+            SnapshotPointReference::Full(id) => (self.utc_timestamp, true, id),
+            SnapshotPointReference::Diff(id) => (self.utc_timestamp, false, id.0),
+        }
+    }
 }
 
 pub struct DiffSequence<Stats> {
@@ -125,13 +162,6 @@ impl<Stats: Debug> Debug for DiffSequence<Stats> {
 }
 
 impl<Stats: Clone> DiffSequence<Stats> {
-    pub fn without_any_diffs(base_point: FullSnapshotPoint<Stats>) -> Self {
-        Self {
-            base_point,
-            diff_points: Vec::new(),
-        }
-    }
-
     pub fn new(base_point: FullSnapshotPoint<Stats>, diff_points: Vec<DiffPoint<Stats>>) -> Self {
         Self {
             base_point,
@@ -139,6 +169,15 @@ impl<Stats: Clone> DiffSequence<Stats> {
         }
     }
 
+    /// 先端のデータ点の時刻。
+    pub fn utc_timestamp_at_the_tip(&self) -> DateTime<Utc> {
+        self.diff_points
+            .last()
+            .map_or(self.base_point.full_snapshot.utc_timestamp, |diff_point| {
+                diff_point.diff.utc_timestamp
+            })
+    }
+
     pub fn into_snapshot_at_the_tip(self) -> StatsSnapshot<Stats> {
         if self.diff_points.is_empty() {
             self.base_point.full_snapshot
@@ -156,6 +195,79 @@ impl<Stats: Clone> DiffSequence<Stats> {
     }
 }
 
+/// `base_snapshot` に `diff_points` を順に適用したスナップショットを、スナップショット全体を構築し直すことなく、
+/// 高々 `chunk_size` 人分ずつに分割して取り出せるようにする。
+pub fn snapshot_chunks_after_applying_diffs<Stats: Send + 'static>(
+    base_snapshot: StatsSnapshot<Stats>,
+    diff_points: Vec<DiffPoint<Stats>>,
+    chunk_size: usize,
+) -> StatsSnapshotChunks<Stats> {
+    let utc_timestamp = diff_points
+        .last()
+        .map_or(base_snapshot.utc_timestamp, |diff_point| {
+            diff_point.diff.utc_timestamp
+        });
+
+    // 各プレーヤーについて、 `diff_points` 内で最後に記録された差分のみを集めたもの
+    let mut latest_player_stats_diffs = HashMap::new();
+    for diff_point in diff_points {
+        latest_player_stats_diffs.extend(diff_point.diff.player_stats_diffs);
+    }
+
+    let base_player_stats = base_snapshot.player_stats;
+    let player_stats_count = {
+        let (mut added, mut removed) = (0, 0);
+        for (uuid, stats) in &latest_player_stats_diffs {
+            let in_base = base_player_stats.contains_key(&Player { uuid: *uuid });
+            match (in_base, stats) {
+                (false, Some(_)) => added += 1,
+                (true, None) => removed += 1,
+                _ => {}
+            }
+        }
+        base_player_stats.len() + added - removed
+    };
+
+    StatsSnapshotChunks::new(
+        utc_timestamp,
+        player_stats_count,
+        PlayerStatsAfterDiffs {
+            base_player_stats: base_player_stats.into_iter(),
+            latest_player_stats_diffs,
+            player_stats_only_in_diffs: None,
+        },
+        chunk_size,
+    )
+}
+
+/// 基準となるスナップショットに差分を適用したスナップショットに含まれるプレーヤーの統計量を、一つずつ返すイテレータ。
+struct PlayerStatsAfterDiffs<Stats> {
+    base_player_stats: std::collections::hash_map::IntoIter<Player, Stats>,
+    latest_player_stats_diffs: HashMap<PlayerUuidString, Option<Stats>>,
+    /// `base_player_stats` を返し終えた後に返す、基準となるスナップショットに存在しないプレーヤーの統計量。
+    player_stats_only_in_diffs:
+        Option<std::collections::hash_map::IntoIter<PlayerUuidString, Option<Stats>>>,
+}
+
+impl<Stats> Iterator for PlayerStatsAfterDiffs<Stats> {
+    type Item = (Player, Stats);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for (player, stats) in self.base_player_stats.by_ref() {
+            match self.latest_player_stats_diffs.remove(&player.uuid) {
+                Some(Some(updated_stats)) => return Some((player, updated_stats)),
+                // tombstone が記録されているプレーヤーは先端のスナップショットに含まれない
+                Some(None) => continue,
+                None => return Some((player, stats)),
+            }
+        }
+
+        self.player_stats_only_in_diffs
+            .get_or_insert_with(|| std::mem::take(&mut self.latest_player_stats_diffs).into_iter())
+            .find_map(|(uuid, stats)| stats.map(|stats| (Player { uuid }, stats)))
+    }
+}
+
 pub enum DiffSequenceChoice<Stats> {
     OptimalAccordingToHeuristics(DiffSequence<Stats>),
     NoAppropriatePointFound,
@@ -201,7 +313,7 @@ fn choose_sub_diff_sequence_for_snapshot_with_heuristics<Stats: Debug + Clone +
 
     struct ScanState<Stats> {
         current_stats_different_from_target_snapshot:
-            HashMap<PlayerUuidString, /* value at target snapshot */ Stats>,
+            HashMap<PlayerUuidString, /* value at target snapshot */ Option<Stats>>,
         current_diff_sequence_depth: usize,
         current_total_diff_size: usize,
     }
@@ -300,7 +412,7 @@ impl<Stats: Clone> IdIndexedDiffPoints<Stats> {
     fn latest(&self) -> Option<&DiffPoint<Stats>> {
         self.0
             .values()
-            .max_by_key(|diff_point| diff_point.diff.utc_timestamp)
+            .max_by_key(|diff_point| (diff_point.diff.utc_timestamp, diff_point.id.0))
     }
 
     fn unsafe_get(&self, id: DiffPointId) -> &DiffPoint<Stats> {
@@ -320,8 +432,19 @@ impl<Stats: Clone> IdIndexedDiffPoints<Stats> {
         self.0.remove(id)
     }
 
-    pub fn map_ids_to_diff_points(mut self, ids: &[DiffPointId]) -> Vec<DiffPoint<Stats>> {
-        ids.iter().map(|id| self.0.remove(id).unwrap()).collect()
+    /// `ids` のそれぞれに対応するデータ点を、 `ids` と同じ順に並べて返す。
+    /// 含まれていない ID があればエラーとする。
+    pub fn map_ids_to_diff_points(
+        mut self,
+        ids: &[DiffPointId],
+    ) -> anyhow::Result<Vec<DiffPoint<Stats>>> {
+        ids.iter()
+            .map(|id| {
+                self.0
+                    .remove(id)
+                    .ok_or_else(|| anyhow::anyhow!("diff point {} does not exist", id.0))
+            })
+            .collect()
     }
 
     fn diff_sequence_towards_latest_diff_point(
@@ -336,7 +459,7 @@ impl<Stats: Clone> IdIndexedDiffPoints<Stats> {
         let diff_points_towards_latest_point = {
             let mut ids = ids_of_diff_points_towards_base_point;
             ids.reverse();
-            self.map_ids_to_diff_points(&ids)
+            self.map_ids_to_diff_points(&ids)?
         };
 
         Ok(DiffSequence::new(
@@ -413,3 +536,105 @@ pub fn choose_base_diff_sequence_for_snapshot_with_heuristics<Stats: Debug + Clo
         optimal_sub_diff_sequence_towards_latest_point,
     ))
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use domain::models::{BreakCount, StatsSnapshot};
+    use domain::test_fixtures::{player, snapshot};
+
+    use super::{snapshot_chunks_after_applying_diffs, ComputeDiff, DiffPoint, DiffPointId};
+
+    fn assert_round_trips(older: &StatsSnapshot<BreakCount>, newer: &StatsSnapshot<BreakCount>) {
+        let restored = older.diff_to(newer).apply_to(older.clone());
+
+        assert_eq!(restored.utc_timestamp, newer.utc_timestamp);
+        assert_eq!(restored.player_stats, newer.player_stats);
+    }
+
+    #[test]
+    fn diff_round_trips_when_players_are_removed() {
+        let older = snapshot(0, &[(1, 10), (2, 20), (3, 30)]);
+        let newer = snapshot(1, &[(1, 10)]);
+
+        let diff = older.diff_to(&newer);
+        assert_eq!(
+            diff.player_stats_diffs,
+            HashMap::from([(player(2).uuid, None), (player(3).uuid, None)])
+        );
+        assert_round_trips(&older, &newer);
+    }
+
+    #[test]
+    fn diff_round_trips_when_removed_players_are_added_back() {
+        let first = snapshot(0, &[(1, 10), (2, 20)]);
+        let second = snapshot(1, &[(1, 10)]);
+        let third = snapshot(2, &[(1, 10), (2, 25)]);
+
+        assert_round_trips(&first, &second);
+        assert_round_trips(&second, &third);
+
+        let restored = second
+            .diff_to(&third)
+            .apply_to(first.diff_to(&second).apply_to(first.clone()));
+        assert_eq!(restored.player_stats, third.player_stats);
+    }
+
+    #[test]
+    fn diff_round_trips_when_players_are_unchanged() {
+        let older = snapshot(0, &[(1, 10), (2, 20)]);
+        let newer = snapshot(1, &[(1, 10), (2, 20)]);
+
+        let diff = older.diff_to(&newer);
+        assert!(diff.player_stats_diffs.is_empty());
+        assert_round_trips(&older, &newer);
+    }
+
+    #[test]
+    fn diff_round_trips_when_players_are_added_changed_and_removed_at_once() {
+        let older = snapshot(0, &[(1, 10), (2, 20), (3, 30)]);
+        let newer = snapshot(1, &[(1, 10), (2, 21), (4, 40)]);
+
+        let diff = older.diff_to(&newer);
+        assert_eq!(
+            diff.player_stats_diffs,
+            HashMap::from([
+                (player(2).uuid, Some(BreakCount(21))),
+                (player(3).uuid, None),
+                (player(4).uuid, Some(BreakCount(40))),
+            ])
+        );
+        assert_round_trips(&older, &newer);
+    }
+
+    #[test]
+    fn chunks_after_applying_diffs_agree_with_the_applied_snapshot() {
+        let snapshots = [
+            snapshot(0, &[(1, 10), (2, 20), (3, 30)]),
+            snapshot(1, &[(1, 10), (2, 21), (4, 40)]),
+            snapshot(2, &[(1, 11), (3, 35), (4, 40), (5, 50)]),
+        ];
+        let diff_points = snapshots
+            .windows(2)
+            .zip(1..)
+            .map(|(pair, id)| DiffPoint {
+                id: DiffPointId(id),
+                previous_diff_point_id: None,
+                diff: pair[0].diff_to(&pair[1]),
+            })
+            .collect::<Vec<_>>();
+
+        let chunks = snapshot_chunks_after_applying_diffs(snapshots[0].clone(), diff_points, 2);
+
+        assert_eq!(chunks.utc_timestamp, snapshots[2].utc_timestamp);
+        assert_eq!(chunks.player_stats_count, snapshots[2].player_stats.len());
+
+        let chunks = chunks.chunks.collect::<Vec<_>>();
+        assert!(chunks.iter().all(|chunk| chunk.len() <= 2));
+        assert_eq!(
+            chunks.into_iter().flatten().collect::<HashMap<_, _>>(),
+            snapshots[2].player_stats
+        );
+    }
+}
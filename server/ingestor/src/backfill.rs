@@ -0,0 +1,118 @@
+use std::collections::HashMap;
+use std::io::BufRead;
+
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+
+use domain::models::{
+    BreakCount, BuildCount, NumericStats, PlayTicks, Player, PlayerUuidString, StatsSnapshot,
+    VoteCount,
+};
+use domain::repositories::PlayerTimedStatsRepository;
+use usecases::BackfillSnapshot;
+
+#[derive(serde::Deserialize, Debug, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+enum StatsKind {
+    BreakCount,
+    BuildCount,
+    PlayTicks,
+    VoteCount,
+}
+
+/// バックフィル用のファイルの一行に書かれる、ある種類の統計量のスナップショット。
+#[derive(serde::Deserialize)]
+struct SnapshotRecord {
+    stats_kind: StatsKind,
+    /// スナップショットが取得された時刻 (RFC 3339 形式)。
+    timestamp: String,
+    /// プレーヤーの UUID から統計量の値への対応。
+    player_stats: HashMap<String, u64>,
+}
+
+impl SnapshotRecord {
+    fn into_snapshot<Stats: NumericStats>(self) -> anyhow::Result<StatsSnapshot<Stats>> {
+        let utc_timestamp = DateTime::parse_from_rfc3339(&self.timestamp)
+            .with_context(|| format!("invalid timestamp {}", self.timestamp))?
+            .with_timezone(&Utc);
+
+        let player_stats = self
+            .player_stats
+            .into_iter()
+            .map(|(uuid, value)| {
+                let player = Player {
+                    uuid: PlayerUuidString::from_string(&uuid)?,
+                };
+                anyhow::Ok((player, Stats::from_raw_value(value)))
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(StatsSnapshot {
+            utc_timestamp,
+            player_stats,
+        })
+    }
+}
+
+/// `source_path` に JSON Lines 形式で書かれたスナップショットを、一行ずつデータ点として後から記録する。
+///
+/// 各行は `{"stats_kind": "break_count", "timestamp": "2022-01-01T00:00:00Z", "player_stats": {"<UUID>": 123}}`
+/// のような形をしている必要がある。行の順序は任意だが、時刻の昇順に並んでいる方が差分を小さく保ちやすい。
+#[tracing::instrument(skip(repository))]
+pub async fn backfill_from_file<Repository>(
+    source_path: &str,
+    repository: &Repository,
+) -> anyhow::Result<()>
+where
+    Repository: PlayerTimedStatsRepository<BreakCount>
+        + PlayerTimedStatsRepository<BuildCount>
+        + PlayerTimedStatsRepository<PlayTicks>
+        + PlayerTimedStatsRepository<VoteCount>
+        + Sync,
+{
+    let reader = std::io::BufReader::new(
+        std::fs::File::open(source_path)
+            .with_context(|| format!("failed to open {source_path}"))?,
+    );
+    let backfill_snapshot = BackfillSnapshot::new(repository);
+
+    for (index, line) in reader.lines().enumerate() {
+        let line_number = index + 1;
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let record = serde_json::from_str::<SnapshotRecord>(&line)
+            .with_context(|| format!("failed to parse line {line_number}"))?;
+        let stats_kind = record.stats_kind;
+
+        match stats_kind {
+            StatsKind::BreakCount => {
+                backfill_snapshot
+                    .execute::<BreakCount>(record.into_snapshot()?)
+                    .await
+            }
+            StatsKind::BuildCount => {
+                backfill_snapshot
+                    .execute::<BuildCount>(record.into_snapshot()?)
+                    .await
+            }
+            StatsKind::PlayTicks => {
+                backfill_snapshot
+                    .execute::<PlayTicks>(record.into_snapshot()?)
+                    .await
+            }
+            StatsKind::VoteCount => {
+                backfill_snapshot
+                    .execute::<VoteCount>(record.into_snapshot()?)
+                    .await
+            }
+        }
+        .with_context(|| format!("failed to backfill line {line_number}"))?;
+
+        tracing::info!(line_number, ?stats_kind, "backfilled a snapshot");
+    }
+
+    Ok(())
+}
@@ -7,3 +7,13 @@ pub struct Sentry {
 
 pub static SENTRY_CONFIG: Lazy<Sentry> =
     Lazy::new(|| envy::prefixed("SENTRY_").from_env::<Sentry>().unwrap());
+
+#[derive(serde::Deserialize, Debug)]
+pub struct Backfill {
+    /// 設定されている場合、上流のデータソースから統計量を取得する代わりに、
+    /// このファイルに書かれた過去のスナップショットをデータ点として後から記録する。
+    pub source_path: Option<String>,
+}
+
+pub static BACKFILL_CONFIG: Lazy<Backfill> =
+    Lazy::new(|| envy::prefixed("BACKFILL_").from_env::<Backfill>().unwrap());
@@ -11,16 +11,19 @@ use tracing_subscriber::Layer;
 
 use domain::models::{BreakCount, BuildCount, PlayTicks, VoteCount};
 use domain::repositories::{PlayerStatsRepository, PlayerTimedStatsRepository};
+use usecases::RecordAllStats;
 
-use crate::config::SENTRY_CONFIG;
+use crate::config::{BACKFILL_CONFIG, SENTRY_CONFIG};
 
+mod backfill;
 mod config;
 
 async fn stats_repository_impl() -> anyhow::Result<
     impl PlayerStatsRepository<BreakCount>
         + PlayerStatsRepository<BuildCount>
         + PlayerStatsRepository<PlayTicks>
-        + PlayerStatsRepository<VoteCount>,
+        + PlayerStatsRepository<VoteCount>
+        + Sync,
 > {
     use infra_upstream_repository_impl::{config::GrpcClient, GrpcUpstreamRepository};
     GrpcUpstreamRepository::try_new(GrpcClient::from_env()?).await
@@ -30,39 +33,28 @@ async fn timed_stats_repository_impl() -> anyhow::Result<
     impl PlayerTimedStatsRepository<BreakCount>
         + PlayerTimedStatsRepository<BuildCount>
         + PlayerTimedStatsRepository<PlayTicks>
-        + PlayerTimedStatsRepository<VoteCount>,
+        + PlayerTimedStatsRepository<VoteCount>
+        + Sync,
 > {
     use infra_db_repository_impl::{config::Database, DatabaseConnector};
     DatabaseConnector::try_new(Database::from_env()?).await
 }
 
-#[tracing::instrument(skip(stats_repository, timed_stats_repository))]
-async fn fetch_and_record<Stats>(
-    stats_repository: &(impl PlayerStatsRepository<Stats> + Sync),
-    timed_stats_repository: &(impl PlayerTimedStatsRepository<Stats> + Sync),
-) -> anyhow::Result<()>
-where
-    Stats: Send + 'static,
-{
-    let snapshot = stats_repository
-        .fetch_stats_snapshot_of_all_players()
-        .await?;
-
-    timed_stats_repository.record_snapshot(snapshot).await?;
-    Ok(())
-}
-
 #[tracing::instrument]
 async fn fetch_and_record_all() -> anyhow::Result<()> {
     let stats_repository = stats_repository_impl().await?;
     let timed_stats_repository = timed_stats_repository_impl().await?;
 
-    fetch_and_record::<BreakCount>(&stats_repository, &timed_stats_repository).await?;
-    fetch_and_record::<BuildCount>(&stats_repository, &timed_stats_repository).await?;
-    fetch_and_record::<PlayTicks>(&stats_repository, &timed_stats_repository).await?;
-    fetch_and_record::<VoteCount>(&stats_repository, &timed_stats_repository).await?;
+    RecordAllStats::new(&stats_repository, &timed_stats_repository)
+        .execute()
+        .await
+}
 
-    Ok(())
+#[tracing::instrument]
+async fn backfill_all(source_path: &str) -> anyhow::Result<()> {
+    let timed_stats_repository = timed_stats_repository_impl().await?;
+
+    backfill::backfill_from_file(source_path, &timed_stats_repository).await
 }
 
 #[tokio::main]
@@ -101,7 +93,11 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     //       at the beginning of a profiled span
     drop(ProfilerGuardBuilder::default().build());
 
-    fetch_and_record_all().await?;
+    if let Some(source_path) = &BACKFILL_CONFIG.source_path {
+        backfill_all(source_path).await?;
+    } else {
+        fetch_and_record_all().await?;
+    }
 
     // hack: そのまま main() を抜けると performance 情報が Sentry に送られないので、バックグラウンドで送ってもらう
     //       送信を一度開始すれば、送信そのものが終了するまでプロセスが抜けることは無さそう
@@ -0,0 +1,23 @@
+use domain::models::StatsSnapshot;
+use domain::repositories::PlayerTimedStatsRepository;
+
+/// 過去の時刻に取得された統計量スナップショットを、データ点として後から記録する。
+pub struct BackfillSnapshot<'a, Repository> {
+    repository: &'a Repository,
+}
+
+impl<'a, Repository: Sync> BackfillSnapshot<'a, Repository> {
+    pub const fn new(repository: &'a Repository) -> Self {
+        Self { repository }
+    }
+
+    /// 既に記録されているデータ点は変更されない。
+    #[tracing::instrument(skip(self), fields(stats_type = std::any::type_name::<Stats>()))]
+    pub async fn execute<Stats>(&self, snapshot: StatsSnapshot<Stats>) -> anyhow::Result<()>
+    where
+        Stats: Send + 'static,
+        Repository: PlayerTimedStatsRepository<Stats>,
+    {
+        PlayerTimedStatsRepository::<Stats>::backfill_snapshot(self.repository, snapshot).await
+    }
+}
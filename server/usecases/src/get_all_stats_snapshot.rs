@@ -0,0 +1,25 @@
+use domain::models::AllStatsSnapshot;
+use domain::repositories::{PlayerAllTimedStatsRepository, TimeBasedSnapshotSearchCondition};
+
+/// すべての種類の統計量について条件に合致するスナップショットを取得し、プレーヤーごとにまとめる。
+pub struct GetAllStatsSnapshot<'a, Repository> {
+    repository: &'a Repository,
+}
+
+impl<'a, Repository: Sync> GetAllStatsSnapshot<'a, Repository> {
+    pub const fn new(repository: &'a Repository) -> Self {
+        Self { repository }
+    }
+
+    /// どの統計量についてもスナップショットが存在しない場合は、プレーヤーを一人も含まないものを返す。
+    #[tracing::instrument(skip(self))]
+    pub async fn execute(
+        &self,
+        condition: TimeBasedSnapshotSearchCondition,
+    ) -> anyhow::Result<AllStatsSnapshot>
+    where
+        Repository: PlayerAllTimedStatsRepository,
+    {
+        self.repository.search_all_stats_snapshot(condition).await
+    }
+}
@@ -0,0 +1,32 @@
+use domain::models::{Player, TimeSeriesPoints, TimestampedRank};
+use domain::repositories::PlayerTimedStatsRepository;
+
+/// あるプレーヤーの統計量の順位の推移を取得する。
+pub struct GetPlayerRankHistory<'a, Repository> {
+    repository: &'a Repository,
+}
+
+impl<'a, Repository: Sync> GetPlayerRankHistory<'a, Repository> {
+    pub const fn new(repository: &'a Repository) -> Self {
+        Self { repository }
+    }
+
+    /// `points` が定める各時刻について、その時刻でのデータ点における `player` の順位を、時刻の昇順に返す。
+    #[tracing::instrument(skip(self), fields(stats_type = std::any::type_name::<Stats>()))]
+    pub async fn execute<Stats>(
+        &self,
+        player: Player,
+        points: TimeSeriesPoints,
+    ) -> anyhow::Result<Vec<TimestampedRank>>
+    where
+        Stats: Send + 'static,
+        Repository: PlayerTimedStatsRepository<Stats>,
+    {
+        PlayerTimedStatsRepository::<Stats>::search_player_rank_history(
+            self.repository,
+            player,
+            points,
+        )
+        .await
+    }
+}
@@ -0,0 +1,28 @@
+use domain::models::{Player, TimestampedStats};
+use domain::repositories::{PlayerTimedStatsRepository, TimeBasedSnapshotSearchCondition};
+
+/// 条件に合致するデータ点での、あるプレーヤーの統計量のみを取得する。
+pub struct GetPlayerStats<'a, Repository> {
+    repository: &'a Repository,
+}
+
+impl<'a, Repository: Sync> GetPlayerStats<'a, Repository> {
+    pub const fn new(repository: &'a Repository) -> Self {
+        Self { repository }
+    }
+
+    /// 条件に合致するデータ点が存在しないか、データ点が `player` の統計量を含まない場合は `None` を返す。
+    #[tracing::instrument(skip(self), fields(stats_type = std::any::type_name::<Stats>()))]
+    pub async fn execute<Stats>(
+        &self,
+        player: Player,
+        condition: TimeBasedSnapshotSearchCondition,
+    ) -> anyhow::Result<Option<TimestampedStats<Stats>>>
+    where
+        Stats: Send + 'static,
+        Repository: PlayerTimedStatsRepository<Stats>,
+    {
+        PlayerTimedStatsRepository::<Stats>::search_player_stats(self.repository, player, condition)
+            .await
+    }
+}
@@ -0,0 +1,36 @@
+use chrono::{DateTime, Utc};
+
+use domain::models::{Player, TimestampedStats};
+use domain::repositories::PlayerTimedStatsRepository;
+
+/// ある期間に記録されたデータ点での、プレーヤーの統計量の履歴を取得する。
+pub struct GetPlayerStatsHistory<'a, Repository> {
+    repository: &'a Repository,
+}
+
+impl<'a, Repository: Sync> GetPlayerStatsHistory<'a, Repository> {
+    pub const fn new(repository: &'a Repository) -> Self {
+        Self { repository }
+    }
+
+    /// `from` 以降 `to` 以前に記録されたデータ点での `player` の統計量を、時刻の昇順に返す。
+    #[tracing::instrument(skip(self), fields(stats_type = std::any::type_name::<Stats>()))]
+    pub async fn execute<Stats>(
+        &self,
+        player: Player,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> anyhow::Result<Vec<TimestampedStats<Stats>>>
+    where
+        Stats: Send + 'static,
+        Repository: PlayerTimedStatsRepository<Stats>,
+    {
+        PlayerTimedStatsRepository::<Stats>::search_player_stats_history(
+            self.repository,
+            player,
+            from,
+            to,
+        )
+        .await
+    }
+}
@@ -0,0 +1,32 @@
+use domain::models::Ranking;
+use domain::repositories::{PlayerTimedStatsRepository, TimeBasedSnapshotSearchCondition};
+
+/// 条件に合致する統計量スナップショットにおける、上位プレーヤーのランキングを取得する。
+pub struct GetRanking<'a, Repository> {
+    repository: &'a Repository,
+}
+
+impl<'a, Repository: Sync> GetRanking<'a, Repository> {
+    pub const fn new(repository: &'a Repository) -> Self {
+        Self { repository }
+    }
+
+    /// 上位 `limit` 人のランキングを返す。
+    /// 条件に合致するスナップショットが存在しない場合は `None` を返す。
+    #[tracing::instrument(skip(self), fields(stats_type = std::any::type_name::<Stats>()))]
+    pub async fn execute<Stats>(
+        &self,
+        condition: TimeBasedSnapshotSearchCondition,
+        limit: usize,
+    ) -> anyhow::Result<Option<Ranking<Stats>>>
+    where
+        Stats: Ord + Clone + Send + 'static,
+        Repository: PlayerTimedStatsRepository<Stats>,
+    {
+        let snapshot =
+            PlayerTimedStatsRepository::<Stats>::search_snapshot(self.repository, condition)
+                .await?;
+
+        Ok(snapshot.map(|snapshot| snapshot.ranking(limit)))
+    }
+}
@@ -0,0 +1,35 @@
+use std::collections::HashSet;
+
+use domain::models::{Player, ResampledStats, TimeSeriesSampling};
+use domain::repositories::PlayerTimedStatsRepository;
+
+/// プレーヤー達の統計量を、一定間隔の時刻で標本化した時系列として取得する。
+pub struct GetResampledPlayerStats<'a, Repository> {
+    repository: &'a Repository,
+}
+
+impl<'a, Repository: Sync> GetResampledPlayerStats<'a, Repository> {
+    pub const fn new(repository: &'a Repository) -> Self {
+        Self { repository }
+    }
+
+    /// `sampling` が定める各時刻について、その時刻以前で最も新しいデータ点での `players` の統計量を、
+    /// 時刻の昇順に返す。
+    #[tracing::instrument(skip(self), fields(stats_type = std::any::type_name::<Stats>()))]
+    pub async fn execute<Stats>(
+        &self,
+        players: HashSet<Player>,
+        sampling: TimeSeriesSampling,
+    ) -> anyhow::Result<Vec<ResampledStats<Stats>>>
+    where
+        Stats: Send + 'static,
+        Repository: PlayerTimedStatsRepository<Stats>,
+    {
+        PlayerTimedStatsRepository::<Stats>::search_resampled_player_stats(
+            self.repository,
+            players,
+            sampling,
+        )
+        .await
+    }
+}
@@ -0,0 +1,26 @@
+use domain::models::StatsSnapshot;
+use domain::repositories::{PlayerTimedStatsRepository, TimeBasedSnapshotSearchCondition};
+
+/// 条件に合致する統計量スナップショットを取得する。
+pub struct GetSnapshot<'a, Repository> {
+    repository: &'a Repository,
+}
+
+impl<'a, Repository: Sync> GetSnapshot<'a, Repository> {
+    pub const fn new(repository: &'a Repository) -> Self {
+        Self { repository }
+    }
+
+    /// 条件に合致するスナップショットが存在しない場合は `None` を返す。
+    #[tracing::instrument(skip(self), fields(stats_type = std::any::type_name::<Stats>()))]
+    pub async fn execute<Stats>(
+        &self,
+        condition: TimeBasedSnapshotSearchCondition,
+    ) -> anyhow::Result<Option<StatsSnapshot<Stats>>>
+    where
+        Stats: Send + 'static,
+        Repository: PlayerTimedStatsRepository<Stats>,
+    {
+        PlayerTimedStatsRepository::<Stats>::search_snapshot(self.repository, condition).await
+    }
+}
@@ -0,0 +1,43 @@
+use anyhow::ensure;
+
+use domain::errors::InvalidArgumentError;
+use domain::models::StatsSnapshotChunks;
+use domain::repositories::{PlayerTimedStatsRepository, TimeBasedSnapshotSearchCondition};
+
+/// 条件に合致する統計量スナップショットを、一定数のプレーヤーごとに分割して取得する。
+pub struct GetSnapshotInChunks<'a, Repository> {
+    repository: &'a Repository,
+}
+
+impl<'a, Repository: Sync> GetSnapshotInChunks<'a, Repository> {
+    pub const fn new(repository: &'a Repository) -> Self {
+        Self { repository }
+    }
+
+    /// スナップショットを高々 `chunk_size` 人分ずつに分割して返す。
+    /// 条件に合致するスナップショットが存在しない場合は `None` を返す。
+    ///
+    /// `chunk_size` が 0 である場合は [`InvalidArgumentError`] となる。
+    #[tracing::instrument(skip(self), fields(stats_type = std::any::type_name::<Stats>()))]
+    pub async fn execute<Stats>(
+        &self,
+        condition: TimeBasedSnapshotSearchCondition,
+        chunk_size: usize,
+    ) -> anyhow::Result<Option<StatsSnapshotChunks<Stats>>>
+    where
+        Stats: Send + 'static,
+        Repository: PlayerTimedStatsRepository<Stats>,
+    {
+        ensure!(
+            chunk_size > 0,
+            InvalidArgumentError("`chunk_size` must be positive".to_owned())
+        );
+
+        PlayerTimedStatsRepository::<Stats>::search_snapshot_in_chunks(
+            self.repository,
+            condition,
+            chunk_size,
+        )
+        .await
+    }
+}
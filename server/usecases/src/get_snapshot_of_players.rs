@@ -0,0 +1,35 @@
+use std::collections::HashSet;
+
+use domain::models::{Player, StatsSnapshot};
+use domain::repositories::{PlayerTimedStatsRepository, TimeBasedSnapshotSearchCondition};
+
+/// 条件に合致する統計量スナップショットのうち、指定されたプレーヤーの統計量のみを取得する。
+pub struct GetSnapshotOfPlayers<'a, Repository> {
+    repository: &'a Repository,
+}
+
+impl<'a, Repository: Sync> GetSnapshotOfPlayers<'a, Repository> {
+    pub const fn new(repository: &'a Repository) -> Self {
+        Self { repository }
+    }
+
+    /// 条件に合致するスナップショットが存在しない場合は `None` を返す。
+    /// スナップショットに含まれていないプレーヤーは、返されるスナップショットにも含まれない。
+    #[tracing::instrument(skip(self), fields(stats_type = std::any::type_name::<Stats>()))]
+    pub async fn execute<Stats>(
+        &self,
+        players: HashSet<Player>,
+        condition: TimeBasedSnapshotSearchCondition,
+    ) -> anyhow::Result<Option<StatsSnapshot<Stats>>>
+    where
+        Stats: Send + 'static,
+        Repository: PlayerTimedStatsRepository<Stats>,
+    {
+        PlayerTimedStatsRepository::<Stats>::search_snapshot_of_players(
+            self.repository,
+            players,
+            condition,
+        )
+        .await
+    }
+}
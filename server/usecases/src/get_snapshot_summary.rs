@@ -0,0 +1,34 @@
+use domain::models::{NumericStats, SnapshotSummary};
+use domain::repositories::{PlayerTimedStatsRepository, TimeBasedSnapshotSearchCondition};
+
+/// 条件に合致する統計量スナップショットの要約統計量を取得する。
+pub struct GetSnapshotSummary<'a, Repository> {
+    repository: &'a Repository,
+}
+
+impl<'a, Repository: Sync> GetSnapshotSummary<'a, Repository> {
+    pub const fn new(repository: &'a Repository) -> Self {
+        Self { repository }
+    }
+
+    /// `percentages` のそれぞれについて百分位数を含めた要約統計量を返す。
+    /// 条件に合致するスナップショットが存在しない場合は `None` を返す。
+    #[tracing::instrument(skip(self), fields(stats_type = std::any::type_name::<Stats>()))]
+    pub async fn execute<Stats>(
+        &self,
+        condition: TimeBasedSnapshotSearchCondition,
+        percentages: &[f64],
+    ) -> anyhow::Result<Option<SnapshotSummary>>
+    where
+        Stats: NumericStats + Send + 'static,
+        Repository: PlayerTimedStatsRepository<Stats>,
+    {
+        let snapshot =
+            PlayerTimedStatsRepository::<Stats>::search_snapshot(self.repository, condition)
+                .await?;
+
+        snapshot
+            .map(|snapshot| snapshot.summarize(percentages))
+            .transpose()
+    }
+}
@@ -0,0 +1,52 @@
+use anyhow::ensure;
+use chrono::{DateTime, Utc};
+
+use domain::errors::InvalidArgumentError;
+use domain::models::{NumericStats, StatsGains};
+use domain::repositories::{PlayerTimedStatsRepository, TimeBasedSnapshotSearchCondition};
+
+/// 二つの時刻の間での、各プレーヤーの統計量の増加量を取得する。
+pub struct GetStatsGains<'a, Repository> {
+    repository: &'a Repository,
+}
+
+impl<'a, Repository: Sync> GetStatsGains<'a, Repository> {
+    pub const fn new(repository: &'a Repository) -> Self {
+        Self { repository }
+    }
+
+    /// `from` 以前の最新のスナップショットと `to` 以前の最新のスナップショットの間での増加量を返す。
+    /// `from` 以前のスナップショットに含まれないプレーヤーは、統計量が 0 から増加したものとして扱う。
+    /// `to` 以前に記録されたスナップショットが存在しない場合は `None` を返す。
+    /// `from` が `to` より後の時刻である場合は [`InvalidArgumentError`] となる。
+    #[tracing::instrument(skip(self), fields(stats_type = std::any::type_name::<Stats>()))]
+    pub async fn execute<Stats>(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> anyhow::Result<Option<StatsGains>>
+    where
+        Stats: NumericStats + Send + 'static,
+        Repository: PlayerTimedStatsRepository<Stats>,
+    {
+        ensure!(
+            from <= to,
+            InvalidArgumentError("`from` must not be later than `to`".to_owned())
+        );
+
+        let snapshot_at_from = PlayerTimedStatsRepository::<Stats>::search_snapshot(
+            self.repository,
+            TimeBasedSnapshotSearchCondition::NewestBefore(from),
+        )
+        .await?;
+
+        let snapshot_at_to = PlayerTimedStatsRepository::<Stats>::search_snapshot(
+            self.repository,
+            TimeBasedSnapshotSearchCondition::NewestBefore(to),
+        )
+        .await?;
+
+        Ok(snapshot_at_to
+            .map(|snapshot_at_to| snapshot_at_to.gains_since(snapshot_at_from.as_ref())))
+    }
+}
@@ -0,0 +1,37 @@
+mod backfill_snapshot;
+mod compare_snapshots;
+mod get_all_stats_snapshot;
+mod get_player_rank_history;
+mod get_player_stats;
+mod get_player_stats_history;
+mod get_ranking;
+mod get_resampled_player_stats;
+mod get_snapshot;
+mod get_snapshot_in_chunks;
+mod get_snapshot_of_players;
+mod get_snapshot_summary;
+mod get_stats_gains;
+mod list_snapshot_points;
+mod poll_recorded_snapshot_points;
+mod record_all_stats;
+mod stats_kind;
+mod timed_stats_repository;
+
+pub use backfill_snapshot::*;
+pub use compare_snapshots::*;
+pub use get_all_stats_snapshot::*;
+pub use get_player_rank_history::*;
+pub use get_player_stats::*;
+pub use get_player_stats_history::*;
+pub use get_ranking::*;
+pub use get_resampled_player_stats::*;
+pub use get_snapshot::*;
+pub use get_snapshot_in_chunks::*;
+pub use get_snapshot_of_players::*;
+pub use get_snapshot_summary::*;
+pub use get_stats_gains::*;
+pub use list_snapshot_points::*;
+pub use poll_recorded_snapshot_points::*;
+pub use record_all_stats::*;
+pub use stats_kind::*;
+pub use timed_stats_repository::*;
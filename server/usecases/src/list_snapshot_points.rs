@@ -0,0 +1,29 @@
+use chrono::{DateTime, Utc};
+
+use domain::models::SnapshotPointMetadata;
+use domain::repositories::PlayerTimedStatsRepository;
+
+/// ある期間に記録されたデータ点の一覧を、統計量を含めずに取得する。
+pub struct ListSnapshotPoints<'a, Repository> {
+    repository: &'a Repository,
+}
+
+impl<'a, Repository: Sync> ListSnapshotPoints<'a, Repository> {
+    pub const fn new(repository: &'a Repository) -> Self {
+        Self { repository }
+    }
+
+    /// `from` 以降 `to` 以前に記録されたデータ点の情報を、時刻の昇順に返す。
+    #[tracing::instrument(skip(self), fields(stats_type = std::any::type_name::<Stats>()))]
+    pub async fn execute<Stats>(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> anyhow::Result<Vec<SnapshotPointMetadata>>
+    where
+        Stats: Send + 'static,
+        Repository: PlayerTimedStatsRepository<Stats>,
+    {
+        PlayerTimedStatsRepository::<Stats>::list_snapshot_points(self.repository, from, to).await
+    }
+}
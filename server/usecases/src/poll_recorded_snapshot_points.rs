@@ -0,0 +1,92 @@
+use std::collections::{HashMap, HashSet};
+
+use domain::models::{LastSnapshotPointIds, RecordedSnapshotPoint, SnapshotPointMetadata};
+use domain::repositories::PlayerTimedStatsRepository;
+
+/// どのデータ点までを既に取得したかを表すもの。
+///
+/// データ点を時刻ではなく ID によって区別するため、過去の時刻のデータ点が後から記録された場合も取りこぼさない。
+#[derive(Debug, Clone)]
+pub struct RecordedSnapshotPointCursor {
+    last_ids: LastSnapshotPointIds,
+}
+
+/// 前回の呼び出し以降に記録されたデータ点を、そのデータ点に記録されている差分レコードと共に取得する。
+pub struct PollRecordedSnapshotPoints<'a, Repository> {
+    repository: &'a Repository,
+}
+
+impl<'a, Repository: Sync> PollRecordedSnapshotPoints<'a, Repository> {
+    pub const fn new(repository: &'a Repository) -> Self {
+        Self { repository }
+    }
+
+    /// 現在までに記録されたデータ点を既に取得したものとみなすカーソルを作る。
+    #[tracing::instrument(skip(self), fields(stats_type = std::any::type_name::<Stats>()))]
+    pub async fn start<Stats>(&self) -> anyhow::Result<RecordedSnapshotPointCursor>
+    where
+        Repository: PlayerTimedStatsRepository<Stats>,
+    {
+        let last_ids =
+            PlayerTimedStatsRepository::<Stats>::last_snapshot_point_ids(self.repository).await?;
+
+        Ok(RecordedSnapshotPointCursor { last_ids })
+    }
+
+    /// `cursor` を作ってから、または前回の呼び出しから後に記録されたデータ点を返し、
+    /// `cursor` を返したデータ点の直後へと進める。
+    ///
+    /// full snapshot point のスナップショット全体は非常に大きくなりうるため、
+    /// 統計量のレコードは diff point についてのみ読み出す。
+    #[tracing::instrument(skip(self), fields(stats_type = std::any::type_name::<Stats>()))]
+    pub async fn execute<Stats>(
+        &self,
+        cursor: &mut RecordedSnapshotPointCursor,
+    ) -> anyhow::Result<Vec<RecordedSnapshotPoint<Stats>>>
+    where
+        Stats: Send + 'static,
+        Repository: PlayerTimedStatsRepository<Stats>,
+    {
+        let new_points = PlayerTimedStatsRepository::<Stats>::list_snapshot_points_recorded_after(
+            self.repository,
+            cursor.last_ids,
+        )
+        .await?;
+
+        let mut recorded_points = Vec::with_capacity(new_points.len());
+        for metadata in new_points {
+            let mut player_stats = HashMap::new();
+            let mut removed_players = HashSet::new();
+
+            if let SnapshotPointMetadata::Diff { .. } = metadata {
+                let records =
+                    PlayerTimedStatsRepository::<Stats>::search_records_at_snapshot_point(
+                        self.repository,
+                        metadata.id(),
+                    )
+                    .await?;
+
+                for (player, stats) in records {
+                    match stats {
+                        Some(stats) => {
+                            player_stats.insert(player, stats);
+                        }
+                        None => {
+                            removed_players.insert(player);
+                        }
+                    }
+                }
+            }
+
+            cursor.last_ids.advance_to(metadata.id());
+
+            recorded_points.push(RecordedSnapshotPoint {
+                metadata,
+                player_stats,
+                removed_players,
+            });
+        }
+
+        Ok(recorded_points)
+    }
+}
@@ -0,0 +1,59 @@
+use domain::models::{BreakCount, BuildCount, PlayTicks, VoteCount};
+use domain::repositories::{PlayerStatsRepository, PlayerTimedStatsRepository};
+
+/// 上流のデータソースからすべての種類の統計量のスナップショットを取得し、データ点として記録する。
+pub struct RecordAllStats<'a, StatsRepository, TimedStatsRepository> {
+    stats_repository: &'a StatsRepository,
+    timed_stats_repository: &'a TimedStatsRepository,
+}
+
+impl<'a, StatsRepository, TimedStatsRepository>
+    RecordAllStats<'a, StatsRepository, TimedStatsRepository>
+where
+    StatsRepository: PlayerStatsRepository<BreakCount>
+        + PlayerStatsRepository<BuildCount>
+        + PlayerStatsRepository<PlayTicks>
+        + PlayerStatsRepository<VoteCount>
+        + Sync,
+    TimedStatsRepository: PlayerTimedStatsRepository<BreakCount>
+        + PlayerTimedStatsRepository<BuildCount>
+        + PlayerTimedStatsRepository<PlayTicks>
+        + PlayerTimedStatsRepository<VoteCount>
+        + Sync,
+{
+    pub const fn new(
+        stats_repository: &'a StatsRepository,
+        timed_stats_repository: &'a TimedStatsRepository,
+    ) -> Self {
+        Self {
+            stats_repository,
+            timed_stats_repository,
+        }
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn execute(&self) -> anyhow::Result<()> {
+        self.fetch_and_record::<BreakCount>().await?;
+        self.fetch_and_record::<BuildCount>().await?;
+        self.fetch_and_record::<PlayTicks>().await?;
+        self.fetch_and_record::<VoteCount>().await?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self), fields(stats_type = std::any::type_name::<Stats>()))]
+    async fn fetch_and_record<Stats>(&self) -> anyhow::Result<()>
+    where
+        Stats: Send + 'static,
+        StatsRepository: PlayerStatsRepository<Stats>,
+        TimedStatsRepository: PlayerTimedStatsRepository<Stats>,
+    {
+        let snapshot = PlayerStatsRepository::<Stats>::fetch_stats_snapshot_of_all_players(
+            self.stats_repository,
+        )
+        .await?;
+
+        PlayerTimedStatsRepository::<Stats>::record_snapshot(self.timed_stats_repository, snapshot)
+            .await
+    }
+}
@@ -0,0 +1,33 @@
+/// サーバーが扱う統計量の種類。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatsKind {
+    BreakCount,
+    BuildCount,
+    PlayTicks,
+    VoteCount,
+}
+
+/// `$stats_kind` ([`StatsKind`]) に対応する統計量の型を `$stats_type` という名前で束縛した上で `$body` を評価する。
+#[macro_export]
+macro_rules! with_stats_type {
+    ($stats_kind:expr, $stats_type:ident => $body:expr) => {
+        match $stats_kind {
+            $crate::StatsKind::BreakCount => {
+                type $stats_type = ::domain::models::BreakCount;
+                $body
+            }
+            $crate::StatsKind::BuildCount => {
+                type $stats_type = ::domain::models::BuildCount;
+                $body
+            }
+            $crate::StatsKind::PlayTicks => {
+                type $stats_type = ::domain::models::PlayTicks;
+                $body
+            }
+            $crate::StatsKind::VoteCount => {
+                type $stats_type = ::domain::models::VoteCount;
+                $body
+            }
+        }
+    };
+}
@@ -0,0 +1,29 @@
+use domain::models::{BreakCount, BuildCount, PlayTicks, VoteCount};
+use domain::repositories::{PlayerAllTimedStatsRepository, PlayerTimedStatsRepository};
+
+/// すべての種類の統計量について、記録されたデータ点を読み書きできるリポジトリ。
+///
+/// [`StatsKind`](crate::StatsKind) によって統計量の種類を選ぶサーバーは、これを実装したリポジトリを扱う。
+pub trait TimedStatsRepository:
+    PlayerTimedStatsRepository<BreakCount>
+    + PlayerTimedStatsRepository<BuildCount>
+    + PlayerTimedStatsRepository<PlayTicks>
+    + PlayerTimedStatsRepository<VoteCount>
+    + PlayerAllTimedStatsRepository
+    + Send
+    + Sync
+    + 'static
+{
+}
+
+impl<T> TimedStatsRepository for T where
+    T: PlayerTimedStatsRepository<BreakCount>
+        + PlayerTimedStatsRepository<BuildCount>
+        + PlayerTimedStatsRepository<PlayTicks>
+        + PlayerTimedStatsRepository<VoteCount>
+        + PlayerAllTimedStatsRepository
+        + Send
+        + Sync
+        + 'static
+{
+}